@@ -3,7 +3,7 @@ use leptos_meta::*;
 use std::fmt::Debug;
 
 use crate::views::character_view::BaseView;
-use crate::server_side::server_functions::{get_char, get_conditions, get_traits, get_feats};
+use crate::server_side::server_functions::{get_ancestries, get_backgrounds, get_char, get_classes, get_companions, get_conditions, get_traits, get_feats, get_spells, get_stat_catalog};
 
 pub fn try_load_source<T:Clone,D:Clone>(source: Resource<T, Result<D, ServerFnError>>, name: &str) -> Option<Result<D, View>> {
     source.get().map(|ketra| {
@@ -36,6 +36,12 @@ pub fn App() -> impl IntoView {
     let feats_source = create_resource(||(), move |_| async move { get_feats().await });
     let conditions_source = create_resource(||(), move |_| async move { get_conditions().await });
     let traits_source = create_resource(||(), move |_| async move { get_traits().await });
+    let classes_source = create_resource(||(), move |_| async move { get_classes().await });
+    let stat_catalog_source = create_resource(||(), move |_| async move { get_stat_catalog().await });
+    let ancestries_source = create_resource(||(), move |_| async move { get_ancestries().await });
+    let backgrounds_source = create_resource(||(), move |_| async move { get_backgrounds().await });
+    let spells_source = create_resource(||(), move |_| async move { get_spells().await });
+    let companions_source = create_resource(||(), move |_| async move { get_companions().await });
     
 
 
@@ -44,13 +50,25 @@ pub fn App() -> impl IntoView {
         let feats_res = try_load_source(feats_source, "Feats")?;
         let traits_res = try_load_source(traits_source, "Traits")?;
         let cond_res = try_load_source(conditions_source, "Conditions")?;
+        let classes_res = try_load_source(classes_source, "Classes")?;
+        let stat_catalog_res = try_load_source(stat_catalog_source, "Skills")?;
+        let ancestries_res = try_load_source(ancestries_source, "Ancestries")?;
+        let backgrounds_res = try_load_source(backgrounds_source, "Backgrounds")?;
+        let spells_res = try_load_source(spells_source, "Spells")?;
+        let companions_res = try_load_source(companions_source, "Companions")?;
 
-        if ketra_res.is_err() || feats_res.is_err() || traits_res.is_err() || cond_res.is_err() {
+        if ketra_res.is_err() || feats_res.is_err() || traits_res.is_err() || cond_res.is_err() || classes_res.is_err() || stat_catalog_res.is_err() || ancestries_res.is_err() || backgrounds_res.is_err() || spells_res.is_err() || companions_res.is_err() {
             Some(Err(view! {
                 {map_resource_ok_to_view(ketra_res, "Ketra")}
                 {map_resource_ok_to_view(feats_res, "Feats")}
                 {map_resource_ok_to_view(traits_res, "Traits")}
                 {map_resource_ok_to_view(cond_res, "Conditions")}
+                {map_resource_ok_to_view(classes_res, "Classes")}
+                {map_resource_ok_to_view(stat_catalog_res, "Skills")}
+                {map_resource_ok_to_view(ancestries_res, "Ancestries")}
+                {map_resource_ok_to_view(backgrounds_res, "Backgrounds")}
+                {map_resource_ok_to_view(spells_res, "Spells")}
+                {map_resource_ok_to_view(companions_res, "Companions")}
             }))
         }
         else {
@@ -60,6 +78,12 @@ pub fn App() -> impl IntoView {
                 feats_res.expect(errmsg),
                 traits_res.expect(errmsg),
                 cond_res.expect(errmsg),
+                classes_res.expect(errmsg),
+                stat_catalog_res.expect(errmsg),
+                ancestries_res.expect(errmsg),
+                backgrounds_res.expect(errmsg),
+                spells_res.expect(errmsg),
+                companions_res.expect(errmsg),
             )))   
         }
     };
@@ -78,8 +102,14 @@ pub fn App() -> impl IntoView {
                                 ketra_char_data,
                                 feat_data,
                                 trait_data,
-                                cond_data
-                            )) => view! { <BaseView char=ketra_char_data feats=feat_data conditions=cond_data trait_data=trait_data/> },
+                                cond_data,
+                                class_data,
+                                stat_catalog,
+                                ancestry_data,
+                                background_data,
+                                spell_data,
+                                companion_data
+                            )) => view! { <BaseView char=ketra_char_data feats=feat_data conditions=cond_data trait_data=trait_data classes=class_data stat_catalog=stat_catalog ancestries=ancestry_data backgrounds=background_data spells=spell_data companions=companion_data/> },
                             Err(error) => error.into_view()
                         }
                     )
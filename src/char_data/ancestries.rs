@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use super::{movement::Size, strikes::UnarmedAttack};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HeritageData {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub unarmed_attacks: Vec<UnarmedAttack>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AncestryData {
+    pub name: String,
+    pub hp: i32,
+    #[serde(default)]
+    pub size: Size,
+    pub speed: i32,
+    /// Attribute ids the ancestry boosts, "free" lets the player choose any attribute
+    pub boosts: Vec<String>,
+    #[serde(default)]
+    pub flaws: Vec<String>,
+    #[serde(default)]
+    pub heritages: Vec<HeritageData>,
+    #[serde(default)]
+    pub unarmed_attacks: Vec<UnarmedAttack>,
+}
+
+impl AncestryData {
+    pub fn get_heritage(&self, heritage_name: &str) -> Option<&HeritageData> {
+        self.heritages.iter().find(|heritage| heritage.name == heritage_name)
+    }
+}
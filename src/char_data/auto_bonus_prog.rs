@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use super::{modifiers::ModifierType, rules::RuleElement};
+
 #[derive(Debug, Clone, Serialize, Default, Deserialize, PartialEq, Eq)]
 pub struct AbpData {
     pub attack_pot : i32,
@@ -9,4 +11,40 @@ pub struct AbpData {
     pub skill_pot : HashMap<String, i32>,
     pub def_pot : i32,
     pub attack_dice : i32,
+}
+
+/// Optional rules of the game the character is played with
+#[derive(Debug, Clone, Serialize, Default, Deserialize, PartialEq, Eq)]
+pub struct VariantRules {
+    /// Potency bonuses and attack dice come from the AbpData instead of runes
+    #[serde(default)]
+    pub automatic_bonus_progression: bool,
+}
+
+impl AbpData {
+    fn potency_rule(selector: &str, label: &str, value: i32) -> RuleElement {
+        RuleElement::FlatModifier {
+            selector: String::from(selector),
+            m_type: ModifierType::Item,
+            value,
+            label: Some(String::from(label)),
+            predicate: vec![],
+        }
+    }
+
+    /// The potency bonuses as rule elements, the damage dice are read directly by the strikes
+    pub fn get_rules(&self) -> Vec<RuleElement> {
+        let mut rules: Vec<RuleElement> = self.skill_pot
+            .iter()
+            .filter(|(_, value)| **value != 0)
+            .map(|(skill_name, value)| AbpData::potency_rule(&skill_name.to_lowercase(), "Skill Potency", *value))
+            .collect();
+        if self.attack_pot != 0 {
+            rules.push(AbpData::potency_rule("attack", "Attack Potency", self.attack_pot));
+        }
+        if self.def_pot != 0 {
+            rules.push(AbpData::potency_rule("ac", "Defense Potency", self.def_pot));
+        }
+        rules
+    }
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BackgroundData {
+    pub name: String,
+    /// The player boosts one of these attributes, the second boost of a background is always free
+    pub boosts: Vec<String>,
+    #[serde(default)]
+    pub trained_skills: Vec<String>,
+    #[serde(default)]
+    pub trained_lore: String,
+    /// Skill feat the background grants
+    #[serde(default)]
+    pub feat: String,
+    #[serde(default)]
+    pub description: String,
+}
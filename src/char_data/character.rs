@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
+use leptos::logging::log;
 use serde::{Deserialize, Serialize};
-use super::{auto_bonus_prog::AbpData, conditions::Condition, gear::Gear, hp::{HpInfo, ShieldInfo}, proficiency::ProficiencyLevel, stats::{Attributes, CalculatedStat, ProficiencyType}, tactics::Tactic};
+use super::{ancestries::AncestryData, auto_bonus_prog::{AbpData, VariantRules}, backgrounds::BackgroundData, classes::{ClassData, ClassFeature}, companion::{Companion, CompanionCatalog}, conditions::{self, Condition}, creature::Creature, feats::{AppliedAddition, CharAddition, Feat, FeatRecord}, gear::{ArmorInfo, Gear, GearType}, hp::{HpInfo, ShieldInfo}, leveling::{has_attribute_boosts, LevelRecord, LevelUpChoices, ProficiencyChange, BOOSTS_PER_BOOST_LEVEL, MAX_LEVEL, PARTIAL_BOOST_THRESHOLD}, modifiers::{Modifier, ModifierType, StatBreakdown}, movement::{MovementType, Size, Speeds}, proficiency::ProficiencyLevel, rules::{self, ActiveRule, RuleElement}, spells::{self, Spell, Spellcasting}, stat_catalog::StatCatalog, stats::{Attribute, Attributes, CalculatedStat, ProficiencyType}, strikes::{Strike, UnarmedAttack, IMPROVISED_PENALTY}, tactics::Tactic};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Character {
@@ -12,17 +13,36 @@ pub struct Character {
     pub shield_info: ShieldInfo,
     
     pub level: i32,
+
+    #[serde(default)]
+    pub size: Size,
+
+    #[serde(default)]
+    pub speeds: Speeds,
     
     pub attributes: Attributes,
     
     #[serde(default)]
     pub text: String,
+
+    /// Skill rolled for initiative, empty rolls Perception
+    #[serde(default)]
+    pub initiative_skill: String,
     
+    #[serde(default)]
+    pub ancestry: String,
+
+    #[serde(default)]
+    pub heritage: String,
+
     #[serde(default)]
     pub background: String,
     
     #[serde(default)]
     pub class: String,
+
+    #[serde(default = "default_key_attribute")]
+    pub key_attribute: String,
     
     #[serde(default)]
     pub proficiencies: Vec<CalculatedStat>,
@@ -35,20 +55,49 @@ pub struct Character {
     
     #[serde(default)]
     pub conditions: Vec<Condition>,
+
+    #[serde(default)]
+    pub level_history: Vec<LevelRecord>,
+
+    /// What the char_additions of each feat changed on the character
+    #[serde(default)]
+    pub feat_records: Vec<FeatRecord>,
+
+    /// Attributes that got one partial boost, a second one raises them
+    #[serde(default)]
+    pub partial_boosts: Vec<String>,
+
+    #[serde(default)]
+    pub spellcasting: Option<Spellcasting>,
+
+    /// Focus points left, the maximum comes from the granted focus spells
+    #[serde(default)]
+    pub focus_points: i32,
     
     #[serde(default)]
     pub gear_list: Vec<Gear>,
     
-    pub horse_hp_info: HpInfo, 
-    
+    /// Animal companions, familiars and minions fighting alongside the character
     #[serde(default)]
-    pub override_prof: HashMap<String, String>,
+    pub companions: Vec<Companion>,
     
     #[serde(default)]
     pub abp_data: AbpData,
-    
+
+    #[serde(default)]
+    pub variants: VariantRules,
+
+    /// Features of the character's class at every level, set from the class data when the origins are applied
+    #[serde(default)]
+    pub class_features: Vec<ClassFeature>,
+
+    /// Unarmed attacks of the ancestry and heritage on top of the fist, set when the origins are applied
     #[serde(default)]
-    pub flags: HashMap<String, bool>
+    pub unarmed_attacks: Vec<UnarmedAttack>,
+
+    /// Rule elements set on the character itself instead of coming from a feat, item, tactic or condition
+    #[serde(default)]
+    pub rules: Vec<RuleElement>
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,17 +108,36 @@ pub struct SimpleCharacter {
 
     pub hp_info: HpInfo,
 
+    #[serde(default)]
+    pub size: Size,
+
+    #[serde(default)]
+    pub speeds: Speeds,
+
     pub attributes: Vec<i32>,
 
     #[serde(default)]
     pub text: String,
 
+    /// Skill rolled for initiative, empty rolls Perception
+    #[serde(default)]
+    pub initiative_skill: String,
+
+    #[serde(default)]
+    pub ancestry: String,
+
+    #[serde(default)]
+    pub heritage: String,
+
     #[serde(default)]
     pub background: String,
 
     #[serde(default)]
     pub class: String,
 
+    #[serde(default = "default_key_attribute")]
+    pub key_attribute: String,
+
     #[serde(default)]
     pub tactics: Vec<Tactic>,
 
@@ -82,87 +150,560 @@ pub struct SimpleCharacter {
     #[serde(default)]
     pub conditions: Vec<Condition>,
 
+    #[serde(default)]
+    pub level_history: Vec<LevelRecord>,
+
+    /// What the char_additions of each feat changed on the character
+    #[serde(default)]
+    pub feat_records: Vec<FeatRecord>,
+
+    /// Attributes that got one partial boost, a second one raises them
+    #[serde(default)]
+    pub partial_boosts: Vec<String>,
+
+    #[serde(default)]
+    pub spellcasting: Option<Spellcasting>,
+
+    /// Focus points left, the maximum comes from the granted focus spells
+    #[serde(default)]
+    pub focus_points: i32,
+
     #[serde(default)]
     pub gear_list: Vec<Gear>,   
 
     pub shield_info: ShieldInfo,
 
-    pub horse_hp_info: HpInfo,
-
     #[serde(default)]
+    pub companions: Vec<Companion>,
+
+    /// Legacy hit points of the horse, loaded as a young Horse companion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub horse_hp_info: Option<HpInfo>,
+
+    /// Legacy attribute overrides, loaded as AbilityOverride rule elements
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub override_prof: HashMap<String, String>,
 
     #[serde(default)]
     pub abp_data: AbpData,
 
+    /// Missing in older saves, which used automatic bonus progression whenever abp data was set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<VariantRules>,
+
+    /// Legacy flags, loaded as RollOption rule elements
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub flags: HashMap<String, bool>,
+
     #[serde(default)]
-    pub flags: HashMap<String, bool>
+    pub rules: Vec<RuleElement>
+}
+
+const BASE_MAX_TACTICS: i32 = 2;
+
+fn default_key_attribute() -> String {
+    String::from("int")
 }
 
 impl Character {
-    pub fn zero() -> Character {
+    pub fn zero(catalog: &StatCatalog) -> Character {
         Character {
             name: String::from(""),
             hp_info: HpInfo::new(0,0,1, 0),
-            horse_hp_info: HpInfo::new(0,0,1, 0),
+            companions: vec![],
             shield_info: ShieldInfo::new(20,18,5, false),
             level: 1,
+            size: Size::default(),
+            speeds: Speeds::default(),
             text: String::from(""),
+            initiative_skill: String::from(""),
             attributes: Attributes::zero(),
+            ancestry: String::from(""),
+            heritage: String::from(""),
             background: String::from("Squire"),
             class: String::from("Commander"),
-            proficiencies: CalculatedStat::default_array(),
+            key_attribute: default_key_attribute(),
+            proficiencies: catalog.default_array(),
             feats: vec![],
             conditions: vec![],
+            level_history: vec![],
+            feat_records: vec![],
+            partial_boosts: vec![],
+            spellcasting: None,
+            focus_points: 0,
             tactics: vec![],
             gear_list: vec![],
-            override_prof: HashMap::new(),
             abp_data: AbpData::default(),
-            flags: HashMap::new(),
+            variants: VariantRules::default(),
+            class_features: vec![],
+            unarmed_attacks: vec![],
+            rules: vec![],
         }
     }
 }
 
 impl Character {
-    fn get_attribute_and_lore_flag_from_skill_name(skill_name: &str, p_type: &ProficiencyType) -> String{
-        return String::from(match p_type {
-            ProficiencyType::Save => {
-                match skill_name {
-                    "Fortitude" => "con",
-                    "Reflex" => "dex",
-                    "Will" => "wis",
-                    _ => {panic!("This save does not exist {skill_name}");}
+    /// Resolves an attribute id, mapping the "key" pseudo id to the character's key attribute
+    pub fn get_attribute_val(self: &Self, id: &str) -> Result<i32, String> {
+        self.get_attribute(id).map(|attribute| attribute.value)
+    }
+
+    pub fn get_class_data<'a>(self: &Self, classes: &'a [ClassData]) -> Result<&'a ClassData, String> {
+        classes
+            .iter()
+            .find(|class_data| class_data.name == self.class)
+            .ok_or(format!("Could not find class data for {0}", self.class))
+    }
+
+    pub fn get_ancestry_data<'a>(self: &Self, ancestries: &'a [AncestryData]) -> Result<&'a AncestryData, String> {
+        ancestries
+            .iter()
+            .find(|ancestry_data| ancestry_data.name == self.ancestry)
+            .ok_or(format!("Could not find ancestry data for {0}", self.ancestry))
+    }
+
+    pub fn get_background_data<'a>(self: &Self, backgrounds: &'a [BackgroundData]) -> Result<&'a BackgroundData, String> {
+        backgrounds
+            .iter()
+            .find(|background_data| background_data.name == self.background)
+            .ok_or(format!("Could not find background data for {0}", self.background))
+    }
+
+    /// Sets size and land speed from the ancestry and trains the starting proficiencies of the background and class, then syncs the rest of the origins.
+    /// Only used when the character is created, afterwards size, speeds and proficiencies belong to the character
+    pub fn apply_origins(self: &mut Self, ancestries: &[AncestryData], backgrounds: &[BackgroundData], classes: &[ClassData], catalog: &StatCatalog) -> Result<(), String> {
+        if !self.ancestry.is_empty() {
+            let ancestry_data = self.get_ancestry_data(ancestries)?;
+            self.size = ancestry_data.size.clone();
+            self.speeds.land = ancestry_data.speed;
+        }
+        if !self.background.is_empty() {
+            let background_data = self.get_background_data(backgrounds)?.clone();
+            for skill in background_data.trained_skills.iter() {
+                self.raise_proficiency(skill, ProficiencyType::Skill, ProficiencyLevel::Trained, catalog)?;
+            }
+            if !background_data.trained_lore.is_empty() {
+                self.raise_proficiency(&background_data.trained_lore, ProficiencyType::Lore, ProficiencyLevel::Trained, catalog)?;
+            }
+        }
+        if !self.class.is_empty() {
+            let class_data = self.get_class_data(classes)?.clone();
+            for skill in class_data.trained_skills.iter() {
+                self.raise_proficiency(skill, ProficiencyType::Skill, ProficiencyLevel::Trained, catalog)?;
+            }
+            for grant in class_data.initial_proficiencies.iter() {
+                self.raise_proficiency(&grant.name, grant.p_type.clone(), grant.proficiency.clone(), catalog)?;
+            }
+        }
+        self.sync_origins(ancestries, classes)
+    }
+
+    /// Derives hit points, class features, unarmed attacks and spellcasting from the ancestry and class the character references.
+    /// Parts the character has not chosen yet, like in saves from before origins existed, keep their saved hit points.
+    /// Runs on every load, so proficiencies are left to the character
+    pub fn sync_origins(self: &mut Self, ancestries: &[AncestryData], classes: &[ClassData]) -> Result<(), String> {
+        let (mut ancestry_hp, mut class_hp) = self.hp_info.get_sources();
+        if !self.ancestry.is_empty() {
+            let ancestry_data = self.get_ancestry_data(ancestries)?.clone();
+            if !self.heritage.is_empty() && ancestry_data.get_heritage(&self.heritage).is_none() {
+                return Err(format!("{0} is not a heritage of the {1} ancestry", self.heritage, ancestry_data.name));
+            }
+            ancestry_hp = ancestry_data.hp;
+            self.unarmed_attacks = ancestry_data.unarmed_attacks.clone();
+            if let Some(heritage_data) = ancestry_data.get_heritage(&self.heritage) {
+                self.unarmed_attacks.extend(heritage_data.unarmed_attacks.iter().cloned());
+            }
+        }
+        if !self.class.is_empty() {
+            let class_data = self.get_class_data(classes)?.clone();
+            class_hp = class_data.hp;
+            self.class_features = class_data.features.clone();
+            if let Some(class_spellcasting) = &class_data.spellcasting {
+                if self.spellcasting.as_ref().is_none_or(|spellcasting| spellcasting.tradition != class_spellcasting.tradition) {
+                    self.spellcasting = Some(Spellcasting::new(class_spellcasting));
                 }
-            },
-            ProficiencyType::Skill => {
-                match skill_name {
-                    "Acrobatics" => "dex",
-                    "Arcana" => "int",
-                    "Athletics" => "str",
-                    "Crafting" => "int",
-                    "Deception" => "cha",
-                    "Diplomacy" => "cha",
-                    "Intimidation" => "cha",
-                    "Medicine" => "wis",
-                    "Nature" => "wis",
-                    "Occultism" => "int",
-                    "Performance" => "cha",
-                    "Religion" => "wis",
-                    "Society" => "int",
-                    "Stealth" => "dex",
-                    "Survival" => "wis",
-                    "Thievery" => "dex",
-                    _ => {panic!("This skill does not exist {skill_name}");}
+            }
+        }
+        self.update_spell_slots();
+        let con = self.get_attribute_val("con")?;
+        self.hp_info.set_sources(ancestry_hp, class_hp);
+        self.hp_info.calculate_max_hp(self.level, con);
+        Ok(())
+    }
+
+    /// Sets a proficiency to at least the given level, adding it if the character does not have it yet.
+    /// Returns the change if there was one
+    pub fn raise_proficiency(self: &mut Self, name: &str, p_type: ProficiencyType, proficiency: ProficiencyLevel, catalog: &StatCatalog) -> Result<Option<ProficiencyChange>, String> {
+        let previous = match self.get_prof_indx_from_name(name) {
+            Some(indx) => {
+                if self.proficiencies[indx].proficiency >= proficiency {
+                    return Ok(None);
                 }
+                let previous = self.proficiencies[indx].proficiency.clone();
+                self.proficiencies[indx].proficiency = proficiency.clone();
+                Some(previous)
             },
-            ProficiencyType::Lore => "int",
-            ProficiencyType::Armor => "dex",
-            ProficiencyType::Weapon => "str",
-            ProficiencyType::Spell => "key",
-            ProficiencyType::ClassDC => "key",
-            ProficiencyType::Perception => "wis",
-        });
-        
+            None => {
+                self.proficiencies.push(catalog.create_stat(name, p_type.clone(), proficiency.clone())?);
+                None
+            },
+        };
+        Ok(Some(ProficiencyChange {
+            name: String::from(name),
+            p_type,
+            previous,
+            new: proficiency,
+        }))
+    }
+
+    /// Undoes a change while the proficiency still has the rank it set, feats the character still has keep the rank they grant.
+    /// Changes that built on this one, like a skill increase on top of a feat, now undo to the rank from before it
+    fn revert_proficiency_change(self: &mut Self, change: &ProficiencyChange) {
+        let Some(indx) = self.get_prof_indx_from_name(&change.name) else {
+            return;
+        };
+        if self.proficiencies[indx].proficiency == change.new {
+            let feat_rank = self.feat_records
+                .iter()
+                .flat_map(|record| record.applied.iter())
+                .filter_map(|applied| match applied {
+                    AppliedAddition::Proficiency {change: feat_change} if feat_change.name == change.name => Some(feat_change.new.clone()),
+                    _ => None,
+                })
+                .max();
+            match change.previous.clone().max(feat_rank) {
+                Some(rank) => self.proficiencies[indx].proficiency = rank,
+                None => {self.proficiencies.remove(indx);},
+            }
+        }
+        for later in self.get_recorded_changes_mut().filter(|later| later.name == change.name && later.previous.as_ref() == Some(&change.new)) {
+            later.previous = change.previous.clone();
+        }
+    }
+
+    pub fn get_max_tactics(self: &Self) -> i32 {
+        let extra_tactics: i32 = self.feat_records
+            .iter()
+            .flat_map(|record| record.applied.iter())
+            .map(|applied| match applied {
+                AppliedAddition::ExtraTactics {value} => *value,
+                _ => 0,
+            })
+            .sum();
+        BASE_MAX_TACTICS + extra_tactics
+    }
+
+    fn apply_addition(self: &mut Self, addition: &CharAddition, catalog: &StatCatalog) -> Result<Option<AppliedAddition>, String> {
+        let applied = match addition {
+            CharAddition::ProfIncrease {name, p_type} => {
+                let next = match self.get_prof_obj_from_name(name) {
+                    Some(stat) => stat.proficiency.next().ok_or(format!("{name} is already legendary"))?,
+                    None => ProficiencyLevel::Trained,
+                };
+                self.raise_proficiency(name, p_type.clone(), next, catalog)?.map(|change| AppliedAddition::Proficiency {change})
+            },
+            CharAddition::ProfSet {name, p_type, proficiency} => {
+                // Kept even if the rank was already reached, so undoing another change does not drop below it
+                let change = self.raise_proficiency(name, p_type.clone(), proficiency.clone(), catalog)?.unwrap_or(ProficiencyChange {
+                    name: name.clone(),
+                    p_type: p_type.clone(),
+                    previous: Some(proficiency.clone()),
+                    new: proficiency.clone(),
+                });
+                Some(AppliedAddition::Proficiency {change})
+            },
+            CharAddition::AttributeOverride {name, attribute} => {
+                self.attributes.get_stat(attribute)?;
+                Some(AppliedAddition::AttributeOverride {name: name.clone(), attribute: attribute.clone()})
+            },
+            CharAddition::Flag {flag} => Some(AppliedAddition::Flag {flag: flag.clone()}),
+            CharAddition::HpPerLevel {value} => Some(AppliedAddition::HpPerLevel {value: *value}),
+            CharAddition::ExtraTactics {value} => Some(AppliedAddition::ExtraTactics {value: *value}),
+        };
+        Ok(applied)
+    }
+
+    fn revert_addition(self: &mut Self, applied: &AppliedAddition) {
+        match applied {
+            AppliedAddition::Proficiency {change} => self.revert_proficiency_change(change),
+            AppliedAddition::AttributeOverride {..} | AppliedAddition::Flag {..} => {},
+            AppliedAddition::HpPerLevel {..} | AppliedAddition::ExtraTactics {..} => {},
+        }
+    }
+
+    /// Applies the char_additions of feats the character gained and reverts the ones of feats it lost.
+    /// Feats missing from the feat map have no additions
+    pub fn sync_feat_additions(self: &mut Self, feat_map: &HashMap<String, Feat>, catalog: &StatCatalog) -> Result<(), String> {
+        while let Some(indx) = self.feat_records.iter().rposition(|record| !self.feats.contains(&record.feat)) {
+            let record = self.feat_records.remove(indx);
+            for applied in record.applied.iter().rev() {
+                self.revert_addition(applied);
+            }
+        }
+        let unapplied_feats: Vec<Feat> = self.feats
+            .iter()
+            .filter(|feat_name| !self.feat_records.iter().any(|record| &record.feat == *feat_name))
+            .filter_map(|feat_name| feat_map.get(feat_name).cloned())
+            .collect();
+        for feat in unapplied_feats {
+            let mut record = FeatRecord {
+                feat: feat.name.clone(),
+                applied: vec![],
+                rules: vec![],
+            };
+            for addition in feat.char_additions.iter() {
+                if let Some(applied) = self.apply_addition(addition, catalog)? {
+                    record.applied.push(applied);
+                }
+            }
+            self.feat_records.push(record);
+        }
+        for record in self.feat_records.iter_mut() {
+            if let Some(feat) = feat_map.get(&record.feat) {
+                record.rules = feat.rules.clone();
+            }
+        }
+        let hp_per_level: i32 = self.feat_records
+            .iter()
+            .flat_map(|record| record.applied.iter())
+            .map(|applied| match applied {
+                AppliedAddition::HpPerLevel {value} => *value,
+                _ => 0,
+            })
+            .sum();
+        self.hp_info.set_bonus_per_level(hp_per_level);
+        self.recalculate_hp()
+    }
+
+    pub fn add_feat(self: &mut Self, feat_name: &str, feat_map: &HashMap<String, Feat>, catalog: &StatCatalog) -> Result<(), String> {
+        if self.feats.iter().any(|known| known == feat_name) {
+            return Err(format!("The character already has the feat {feat_name}"));
+        }
+        self.feats.push(String::from(feat_name));
+        self.sync_feat_additions(feat_map, catalog)
+    }
+
+    pub fn remove_feat(self: &mut Self, feat_name: &str, feat_map: &HashMap<String, Feat>, catalog: &StatCatalog) -> Result<(), String> {
+        self.feats.retain(|known| known != feat_name);
+        self.sync_feat_additions(feat_map, catalog)
+    }
+
+    fn recalculate_hp(self: &mut Self) -> Result<(), String> {
+        let con = self.get_attribute_val("con")?;
+        self.hp_info.calculate_max_hp(self.level, con);
+        Ok(())
+    }
+
+    /// Recalculates the hit points of every companion for the character's level
+    pub fn sync_companions(self: &mut Self, catalog: &CompanionCatalog) -> Result<(), String> {
+        let level = self.level;
+        self.companions.iter_mut().try_for_each(|companion| companion.sync(level, catalog))
+    }
+
+    pub fn get_companion_mut(self: &mut Self, indx: usize) -> Result<&mut Companion, String> {
+        let name = self.name.clone();
+        self.companions.get_mut(indx).ok_or(format!("{name} has no companion number {indx}"))
+    }
+
+    /// Hit points of the character, or of the companion at the given index
+    pub fn get_hp_info(self: &Self, companion: Option<usize>) -> Result<&HpInfo, String> {
+        match companion {
+            Some(indx) => self.companions.get(indx).map(|companion| &companion.hp_info).ok_or(format!("{0} has no companion number {indx}", self.name)),
+            None => Ok(&self.hp_info),
+        }
+    }
+
+    pub fn get_hp_info_mut(self: &mut Self, companion: Option<usize>) -> Result<&mut HpInfo, String> {
+        match companion {
+            Some(indx) => Ok(&mut self.get_companion_mut(indx)?.hp_info),
+            None => Ok(&mut self.hp_info),
+        }
+    }
+
+    pub fn add_companion(self: &mut Self, name: &str, companion_type: &str, catalog: &CompanionCatalog) -> Result<(), String> {
+        if self.companions.iter().any(|companion| companion.name == name) {
+            return Err(format!("{0} already has a companion named {name}", self.name));
+        }
+        let mut companion = Companion::new(name, companion_type);
+        companion.advancement = catalog.get_type(companion_type)?.default_advancement.clone();
+        companion.sync(self.level, catalog)?;
+        self.companions.push(companion);
+        Ok(())
+    }
+
+    pub fn remove_companion(self: &mut Self, indx: usize) {
+        if indx < self.companions.len() {
+            self.companions.remove(indx);
+        }
+    }
+
+    /// Boosts an attribute by one, or only partially once it is at +4 or above
+    fn apply_boost(self: &mut Self, attribute_id: &str) -> Result<(), String> {
+        let value = self.attributes.get_stat_val(attribute_id)?;
+        if value < PARTIAL_BOOST_THRESHOLD {
+            self.attributes.set_stat(attribute_id, value + 1);
+        }
+        else if let Some(indx) = self.partial_boosts.iter().position(|partial| partial == attribute_id) {
+            self.partial_boosts.remove(indx);
+            self.attributes.set_stat(attribute_id, value + 1);
+        }
+        else {
+            self.partial_boosts.push(String::from(attribute_id));
+        }
+        Ok(())
+    }
+
+    /// Checks the choices for the next level without changing the character, returns the skill increase if one was chosen
+    fn validate_level_up(self: &Self, choices: &LevelUpChoices, class_data: &ClassData, feat_map: &HashMap<String, Feat>) -> Result<Option<ProficiencyChange>, String> {
+        let new_level = self.level + 1;
+        if new_level > MAX_LEVEL {
+            return Err(format!("Level {MAX_LEVEL} is the highest level"));
+        }
+        if has_attribute_boosts(new_level) {
+            if choices.boosts.len() != BOOSTS_PER_BOOST_LEVEL {
+                return Err(format!("Level {new_level} needs {BOOSTS_PER_BOOST_LEVEL} attribute boosts"));
+            }
+            for (indx, boost) in choices.boosts.iter().enumerate() {
+                self.attributes.get_stat(boost)?;
+                if choices.boosts[..indx].contains(boost) {
+                    return Err(format!("{boost} can only be boosted once per level"));
+                }
+            }
+        }
+        else if !choices.boosts.is_empty() {
+            return Err(format!("Level {new_level} gives no attribute boosts"));
+        }
+        let feat_slots = class_data.get_feat_slots(new_level);
+        if choices.feats.len() > feat_slots.len() {
+            return Err(format!("Level {new_level} only has {0} feat slots", feat_slots.len()));
+        }
+        // The feats are chosen per slot, empty slots are skipped
+        for (feat_name, feat_type) in choices.feats.iter().zip(feat_slots.iter()).filter(|(feat_name, _)| !feat_name.is_empty()) {
+            let feat = feat_map.get(feat_name).ok_or(format!("There is no feat called {feat_name}"))?;
+            if !feat.feat_types.contains(feat_type) {
+                return Err(format!("{feat_name} can not be taken as a {feat_type:?} feat"));
+            }
+            if feat.level > new_level {
+                return Err(format!("{feat_name} is a level {0} feat", feat.level));
+            }
+        }
+        if choices.skill_increase.is_empty() {
+            return Ok(None);
+        }
+        if !class_data.has_skill_increase(new_level) {
+            return Err(format!("Level {new_level} gives no skill increase"));
+        }
+        let stat = self.get_prof_obj_from_name(&choices.skill_increase).ok_or(format!("There is no skill called {0}", choices.skill_increase))?;
+        if stat.p_type != ProficiencyType::Skill && stat.p_type != ProficiencyType::Lore {
+            return Err(format!("{0} is not a skill", stat.name));
+        }
+        let next = stat.proficiency.next().ok_or(format!("{0} is already legendary", stat.name))?;
+        if next.get_min_level() > new_level {
+            return Err(format!("{0} can only become {next} at level {1}", stat.name, next.get_min_level()));
+        }
+        Ok(Some(ProficiencyChange {
+            name: stat.name.clone(),
+            p_type: stat.p_type.clone(),
+            previous: Some(stat.proficiency.clone()),
+            new: next,
+        }))
+    }
+
+    /// Raises the character one level and records the chosen boosts, skill increase and feats together with the class features of the new level.
+    /// The level is built on a copy, so a failing lookup leaves the character as it was
+    pub fn level_up(self: &mut Self, choices: &LevelUpChoices, classes: &[ClassData], feat_map: &HashMap<String, Feat>, catalog: &StatCatalog) -> Result<(), String> {
+        let mut leveled = self.clone();
+        leveled.apply_level_up(choices, classes, feat_map, catalog)?;
+        *self = leveled;
+        Ok(())
+    }
+
+    fn apply_level_up(self: &mut Self, choices: &LevelUpChoices, classes: &[ClassData], feat_map: &HashMap<String, Feat>, catalog: &StatCatalog) -> Result<(), String> {
+        let class_data = self.get_class_data(classes)?.clone();
+        let skill_increase = self.validate_level_up(choices, &class_data, feat_map)?;
+        let new_level = self.level + 1;
+        let mut record = LevelRecord {
+            level: new_level,
+            boosts: choices.boosts.clone(),
+            previous_attributes: vec![],
+            previous_partial_boosts: vec![],
+            proficiency_changes: vec![],
+            feats: vec![],
+            class_features: vec![],
+        };
+        self.level = new_level;
+        if !choices.boosts.is_empty() {
+            record.previous_attributes = self.attributes.as_number_vec();
+            record.previous_partial_boosts = self.partial_boosts.clone();
+            for boost in choices.boosts.iter() {
+                self.apply_boost(boost)?;
+            }
+        }
+        if let Some(change) = skill_increase {
+            if let Some(indx) = self.get_prof_indx_from_name(&change.name) {
+                self.proficiencies[indx].proficiency = change.new.clone();
+            }
+            record.proficiency_changes.push(change);
+        }
+        for feature in class_data.get_features_at_level(new_level) {
+            record.class_features.push(feature.name.clone());
+            for grant in feature.proficiencies.iter() {
+                if let Some(change) = self.raise_proficiency(&grant.name, grant.p_type.clone(), grant.proficiency.clone(), catalog)? {
+                    record.proficiency_changes.push(change);
+                }
+            }
+        }
+        for feat_name in choices.feats.iter().filter(|feat_name| !feat_name.is_empty()) {
+            if !self.feats.contains(feat_name) {
+                self.feats.push(feat_name.clone());
+                record.feats.push(feat_name.clone());
+            }
+        }
+        self.level_history.push(record);
+        self.update_spell_slots();
+        self.recalculate_hp()
+    }
+
+    /// Lowers the character one level and undoes what that level granted. Levels from before the history was kept only lose their hit points
+    pub fn level_down(self: &mut Self) -> Result<(), String> {
+        if self.level <= 1 {
+            return Err(String::from("Level 1 is the lowest level"));
+        }
+        if self.level_history.last().is_some_and(|record| record.level == self.level) {
+            let record = self.level_history.pop().expect("The last level record was just checked");
+            for change in record.proficiency_changes.iter().rev() {
+                self.revert_proficiency_change(change);
+            }
+            self.feats.retain(|feat_name| !record.feats.contains(feat_name));
+            if !record.previous_attributes.is_empty() {
+                self.attributes = Attributes::from(&record.previous_attributes);
+                self.partial_boosts = record.previous_partial_boosts;
+            }
+        }
+        self.level -= 1;
+        self.update_spell_slots();
+        self.recalculate_hp()
+    }
+
+    /// Checks that the key attribute is one of the options the character's class allows
+    pub fn validate_key_attribute(self: &Self, classes: &[ClassData]) -> Result<(), String> {
+        let class_data = self.get_class_data(classes)?;
+        if class_data.key_attributes.contains(&self.key_attribute) {
+            Ok(())
+        }
+        else {
+            Err(format!("{0} is not a valid key attribute for {1}, options are {2:?}", self.key_attribute, self.class, class_data.key_attributes))
+        }
+    }
+
+    pub fn set_key_attribute(self: &mut Self, attribute_id: &str, classes: &[ClassData]) -> Result<(), String> {
+        let previous = self.key_attribute.clone();
+        self.key_attribute = String::from(attribute_id);
+        let validation = self.validate_key_attribute(classes);
+        if validation.is_err() {
+            self.key_attribute = previous;
+        }
+        validation
     }
 
     pub fn get_prof_obj_from_name(self: &Self, skill_name: &str) -> Option<CalculatedStat>{
@@ -171,6 +712,130 @@ impl Character {
         .find(|prof| prof.name==skill_name).cloned();
     }
 
+    fn get_lore_indx(self: &Self, lore_name: &str) -> Result<usize, String> {
+        self.proficiencies
+            .iter()
+            .position(|prof| prof.name == lore_name && prof.p_type == ProficiencyType::Lore)
+            .ok_or(format!("There is no lore with the name {lore_name}"))
+    }
+
+    /// Lores are always named "<Topic> Lore", the suffix is added if it is missing
+    fn normalize_lore_name(lore_name: &str) -> Result<String, String> {
+        let trimmed = lore_name.trim();
+        if trimmed.is_empty() {
+            return Err(String::from("A lore needs a name"));
+        }
+        if trimmed.to_lowercase().ends_with(" lore") || trimmed.to_lowercase() == "lore" {
+            Ok(String::from(trimmed))
+        }
+        else {
+            Ok(format!("{trimmed} Lore"))
+        }
+    }
+
+    pub fn add_lore(self: &mut Self, lore_name: &str, proficiency: ProficiencyLevel, catalog: &StatCatalog) -> Result<(), String> {
+        let name = Character::normalize_lore_name(lore_name)?;
+        if self.get_prof_indx_from_name(&name).is_some() {
+            return Err(format!("There already is a proficiency called {name}"));
+        }
+        self.proficiencies.push(catalog.create_stat(&name, ProficiencyType::Lore, proficiency)?);
+        Ok(())
+    }
+
+    /// Feats and class features whose data names the lore. Their rules are refreshed from the data on every load and
+    /// class features of later levels grant proficiencies by name, so neither would follow a rename
+    fn get_lore_references(self: &Self, lore_name: &str) -> Vec<String> {
+        let lore_selector = lore_name.to_lowercase();
+        let targets_lore = |rules: &Vec<RuleElement>| rules.iter().any(|rule| rule.get_selector() == Some(&lore_selector));
+        let feat_references = self.feat_records
+            .iter()
+            .filter(|record| targets_lore(&record.rules))
+            .map(|record| record.feat.clone());
+        let feature_references = self.class_features
+            .iter()
+            .filter(|feature| targets_lore(&feature.rules) || (feature.level > self.level && feature.proficiencies.iter().any(|grant| grant.name == lore_name)))
+            .map(|feature| feature.name.clone());
+        feat_references.chain(feature_references).collect()
+    }
+
+    /// Every proficiency change the level history and the feat records kept
+    fn get_recorded_changes_mut(self: &mut Self) -> impl Iterator<Item = &mut ProficiencyChange> {
+        self.level_history
+            .iter_mut()
+            .flat_map(|record| record.proficiency_changes.iter_mut())
+            .chain(self.feat_records.iter_mut().flat_map(|record| record.applied.iter_mut()).filter_map(|applied| match applied {
+                AppliedAddition::Proficiency {change} => Some(change),
+                _ => None,
+            }))
+    }
+
+    /// Rule elements stored on the character, its gear and its tactics
+    fn get_own_rules_mut(self: &mut Self) -> impl Iterator<Item = &mut RuleElement> {
+        self.rules
+            .iter_mut()
+            .chain(self.gear_list.iter_mut().flat_map(|item| item.rules.iter_mut()))
+            .chain(self.tactics.iter_mut().flat_map(|tactic| tactic.rules.iter_mut()))
+    }
+
+    /// Renames a lore and moves its skill potency, rules, recorded changes and initiative choice along with it.
+    /// Lores that feats or class features refer to by name keep their name
+    pub fn rename_lore(self: &mut Self, old_name: &str, new_name: &str) -> Result<(), String> {
+        let indx = self.get_lore_indx(old_name)?;
+        let name = Character::normalize_lore_name(new_name)?;
+        if name == old_name {
+            return Ok(());
+        }
+        if self.get_prof_indx_from_name(&name).is_some() {
+            return Err(format!("There already is a proficiency called {name}"));
+        }
+        let references = self.get_lore_references(old_name);
+        if !references.is_empty() {
+            return Err(format!("{old_name} can't be renamed, it is named in {0}", references.join(", ")));
+        }
+        self.proficiencies[indx].name = name.clone();
+        if let Some(potency) = self.abp_data.skill_pot.remove(old_name) {
+            self.abp_data.skill_pot.insert(name.clone(), potency);
+        }
+        let old_selector = old_name.to_lowercase();
+        for selector in self.get_own_rules_mut().filter_map(|rule| rule.get_selector_mut()).filter(|selector| **selector == old_selector) {
+            *selector = name.to_lowercase();
+        }
+        for change in self.get_recorded_changes_mut().filter(|change| change.name == old_name) {
+            change.name = name.clone();
+        }
+        for applied in self.feat_records.iter_mut().flat_map(|record| record.applied.iter_mut()) {
+            if let AppliedAddition::AttributeOverride {name: override_name, ..} = applied {
+                if override_name == old_name {
+                    *override_name = name.clone();
+                }
+            }
+        }
+        if self.initiative_skill == old_name {
+            self.initiative_skill = name;
+        }
+        Ok(())
+    }
+
+    /// Removes a lore together with its skill potency, its custom rules and the changes levels and feats recorded for it,
+    /// so a lore added later under the same name starts fresh
+    pub fn remove_lore(self: &mut Self, lore_name: &str) -> Result<(), String> {
+        let indx = self.get_lore_indx(lore_name)?;
+        self.proficiencies.remove(indx);
+        self.abp_data.skill_pot.remove(lore_name);
+        let lore_selector = lore_name.to_lowercase();
+        self.rules.retain(|rule| rule.get_selector() != Some(&lore_selector));
+        for record in self.level_history.iter_mut() {
+            record.proficiency_changes.retain(|change| change.name != lore_name);
+        }
+        for record in self.feat_records.iter_mut() {
+            record.applied.retain(|applied| !matches!(applied, AppliedAddition::Proficiency {change} if change.name == lore_name));
+        }
+        if self.initiative_skill == lore_name {
+            self.initiative_skill = String::from("");
+        }
+        Ok(())
+    }
+
     pub fn get_prof_indx_from_name(self: &Self, skill_name: &str) -> Option<usize>{
         for (indx, skill) in self.proficiencies.iter().enumerate() {
             if skill.name == skill_name {
@@ -181,75 +846,401 @@ impl Character {
     }
 
     pub fn calculate_ac(self: & Self) -> i32 {
-        let calc_stat = self.get_prof_obj_from_name("Medium").expect("Character must have a medium proficiency");
-        let dex_cap = 1;
-        let item_bonus = 4;
-        let prof_bonus = calc_stat.proficiency.get_bonus(self.level);
-        let raised_bonus = if self.shield_info.raised {2} else {0};
-        10 + std::cmp::min(self.attributes.get_stat_val("dex").expect("Defense expects a dex attribute to be set"), dex_cap) + prof_bonus + item_bonus + raised_bonus
+        self.calculate_ac_breakdown().total
     }
-}
 
+    pub fn calculate_ac_breakdown(self: &Self) -> StatBreakdown {
+        let worn_armor = self.get_worn_armor();
+        let armor_info = match worn_armor {
+            Some(armor) => armor.armor_info.clone().expect("Worn armor is expected to have armor info"),
+            None => ArmorInfo::unarmored(),
+        };
+        let prof_name = armor_info.category.get_proficiency_name();
+        let proficiency = match self.get_prof_obj_from_name(&prof_name) {
+            Some(calc_stat) => calc_stat.proficiency,
+            None => {log!("Character has no {prof_name} armor proficiency"); ProficiencyLevel::Untrained},
+        };
+        let item_bonus = worn_armor.and_then(|armor| {
+            let potency = if self.variants.automatic_bonus_progression {0} else {armor_info.runes.potency};
+            let item_bonus = armor_info.ac_bonus + potency;
+            (item_bonus != 0).then(|| Modifier::new(&armor.name, ModifierType::Item, item_bonus))
+        });
+        self.calculate_armor_class(&prof_name, &proficiency, armor_info.dex_cap, item_bonus).expect("Defense expects a dex attribute to be set")
+    }
 
-impl From<SimpleCharacter> for Character{
-    fn from(simp_char: SimpleCharacter) -> Self {
-        let mut ret_val = Character {
-            name: simp_char.name,
-            hp_info: simp_char.hp_info,
-            horse_hp_info: simp_char.horse_hp_info,
-            shield_info: simp_char.shield_info,
-            level: simp_char.level,
-            text: simp_char.text,
-            attributes: Attributes::from(&simp_char.attributes),
-            background: simp_char.background,
-            class: simp_char.class,
-            proficiencies: vec![],
-            feats: simp_char.feats,
-            conditions: simp_char.conditions,
-            tactics: simp_char.tactics,
-            gear_list: simp_char.gear_list,
-            override_prof: simp_char.override_prof,
-            abp_data: simp_char.abp_data,
-            flags: simp_char.flags
+    /// The gear list together with items granted by rule elements
+    pub fn get_all_gear(self: &Self) -> Vec<Gear> {
+        let mut all_gear = self.gear_list.clone();
+        all_gear.extend(rules::get_granted_items(&self.get_active_rules()));
+        all_gear
+    }
+
+    /// The fist and the unarmed attacks of the character's origins, as weapons
+    pub fn get_unarmed_attacks(self: &Self) -> Vec<Gear> {
+        let mut attacks = vec![UnarmedAttack::fist().to_gear()];
+        attacks.extend(self.unarmed_attacks.iter().map(|attack| attack.to_gear()));
+        attacks
+    }
+
+    /// Every weapon from the gear list, granted items and unarmed attacks
+    pub fn get_weapons(self: &Self) -> Vec<Gear> {
+        let mut weapons: Vec<Gear> = self.get_all_gear().into_iter().filter(|item| item.g_type == GearType::Weapon).collect();
+        weapons.extend(self.get_unarmed_attacks());
+        weapons
+    }
+
+    pub fn get_strike(self: &Self, weapon_name: &str) -> Result<Strike, String> {
+        let weapon = self.get_weapons()
+            .into_iter()
+            .find(|item| item.name == weapon_name)
+            .ok_or(format!("{0} has no weapon named {weapon_name}", self.name))?;
+        Strike::from_weapon(&weapon, self)
+    }
+
+    /// Errors if the weapon needs ammunition and there is none left
+    pub fn check_ammunition(self: &Self, weapon_name: &str) -> Result<(), String> {
+        let Some(ammunition) = self.get_strike(weapon_name)?.ammunition else {
+            return Ok(());
         };
+        let ammo = self.gear_list
+            .iter()
+            .find(|item| item.name == ammunition)
+            .ok_or(format!("{weapon_name} needs {ammunition}, which is not in the gear list"))?;
+        match ammo.quantity {
+            Some(quantity) if quantity > 0 => Ok(()),
+            _ => Err(format!("There is no {ammunition} left")),
+        }
+    }
 
-        for skill_tuple in simp_char.proficiencies {
-            let attribute = Character::get_attribute_and_lore_flag_from_skill_name(skill_tuple.0.as_str(), &skill_tuple.1);
-            ret_val.proficiencies.push(CalculatedStat::new(skill_tuple.1, &attribute, skill_tuple.0.as_str(), skill_tuple.2))
+    /// Uses up one piece of the weapon's ammunition, weapons without ammunition need none
+    pub fn use_ammunition(self: &mut Self, weapon_name: &str) -> Result<(), String> {
+        self.check_ammunition(weapon_name)?;
+        let Some(ammunition) = self.get_strike(weapon_name)?.ammunition else {
+            return Ok(());
+        };
+        if let Some(ammo) = self.gear_list.iter_mut().find(|item| item.name == ammunition) {
+            ammo.quantity = ammo.quantity.map(|quantity| quantity - 1);
         }
+        Ok(())
+    }
 
-        return ret_val;
+    /// Gives the conditions rule elements grant and removes the ones whose source no longer grants them
+    pub fn sync_granted_conditions(self: &mut Self, catalog: &[Condition]) -> Result<(), String> {
+        let granted = rules::get_granted_conditions(&self.get_active_rules());
+        let condition_names: Vec<String> = self.conditions.iter().map(|cond| cond.name.clone()).collect();
+        let stale: Vec<(String, String)> = self.conditions
+            .iter()
+            .flat_map(|cond| cond.implied_by
+                .iter()
+                .filter(|source| !condition_names.contains(source))
+                .filter(|source| !granted.iter().any(|(g_source, g_name, _)| g_source == *source && *g_name == cond.name))
+                .map(|source| (source.clone(), cond.name.clone())))
+            .collect();
+        for (source, name) in stale {
+            conditions::revoke_source(&mut self.conditions, catalog, &source, Some(&name));
+        }
+        for (source, name, value) in granted {
+            conditions::grant_condition(&mut self.conditions, catalog, &name, value, &source)?;
+        }
+        Ok(())
+    }
+
+    /// Perception, or the skill chosen for the exploration activity before the encounter
+    pub fn get_initiative_stat(self: &Self) -> Result<CalculatedStat, String> {
+        let stat_name = if self.initiative_skill.is_empty() {"Perception"} else {&self.initiative_skill};
+        match self.get_prof_obj_from_name(stat_name) {
+            Some(stat) if stat.p_type == ProficiencyType::Perception || stat.p_type == ProficiencyType::Skill || stat.p_type == ProficiencyType::Lore => Ok(stat),
+            Some(_) => Err(format!("Initiative can't be rolled with {stat_name}")),
+            None => Err(format!("Could not find {stat_name} for initiative")),
+        }
+    }
+
+    /// Breakdown of the initiative stat, including modifiers that only apply to initiative
+    pub fn calculate_initiative_breakdown(self: &Self) -> Result<StatBreakdown, String> {
+        self.get_initiative_stat()?.calculate_stat_breakdown_with(self, &[String::from("initiative")])
+    }
+
+    /// Sets the skill to roll initiative with, Perception or an empty name go back to rolling Perception
+    pub fn set_initiative_skill(self: &mut Self, skill_name: &str) -> Result<(), String> {
+        let previous = std::mem::replace(&mut self.initiative_skill, if skill_name == "Perception" {String::from("")} else {String::from(skill_name)});
+        if let Err(err) = self.get_initiative_stat() {
+            self.initiative_skill = previous;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn update_spell_slots(self: &mut Self) {
+        if let Some(spellcasting) = self.spellcasting.as_mut() {
+            spellcasting.update_slots(self.level);
+        }
+    }
+
+    pub fn get_spellcasting_mut(self: &mut Self) -> Result<&mut Spellcasting, String> {
+        let name = self.name.clone();
+        self.spellcasting.as_mut().ok_or(format!("{name} can't cast spells"))
+    }
+
+    /// The Spell proficiency of the character's tradition
+    pub fn get_spell_proficiency(self: &Self) -> Result<CalculatedStat, String> {
+        let spellcasting = self.spellcasting.as_ref().ok_or(format!("{0} can't cast spells", self.name))?;
+        self.proficiencies
+            .iter()
+            .find(|prof| prof.p_type == ProficiencyType::Spell && prof.name == spellcasting.tradition)
+            .cloned()
+            .ok_or(format!("Could not find the {0} spell proficiency", spellcasting.tradition))
+    }
+
+    pub fn calculate_spell_attack_breakdown(self: &Self) -> Result<StatBreakdown, String> {
+        self.get_spell_proficiency()?.calculate_stat_breakdown_with(self, &[String::from("spell-attack"), String::from("check")])
+    }
+
+    pub fn calculate_spell_dc_breakdown(self: &Self) -> Result<StatBreakdown, String> {
+        let breakdown = self.get_spell_proficiency()?.calculate_stat_breakdown_with(self, &[String::from("spell-dc"), String::from("dc")])?;
+        Ok(breakdown.with_base(10))
+    }
+
+    pub fn get_focus_spells(self: &Self, spells: &[Spell]) -> Vec<Spell> {
+        rules::get_focus_spells(&self.get_active_rules())
+            .iter()
+            .filter_map(|spell_name| spells.iter().find(|spell| spell.name == *spell_name).cloned())
+            .collect()
+    }
+
+    /// One point for every focus spell that is not a cantrip, up to three
+    pub fn get_max_focus_points(self: &Self, spells: &[Spell]) -> i32 {
+        let count = self.get_focus_spells(spells).iter().filter(|spell| !spell.is_cantrip()).count() as i32;
+        std::cmp::min(count, spells::MAX_FOCUS_POINTS)
+    }
+
+    /// Points left, a pool that shrank since the points were saved caps them
+    pub fn get_focus_points(self: &Self, spells: &[Spell]) -> i32 {
+        std::cmp::min(self.focus_points, self.get_max_focus_points(spells))
+    }
+
+    /// Casts a focus spell, spending a focus point unless it is a cantrip
+    pub fn cast_focus_spell(self: &mut Self, spell_name: &str, spells: &[Spell]) -> Result<(), String> {
+        let spell = self.get_focus_spells(spells)
+            .into_iter()
+            .find(|spell| spell.name == spell_name)
+            .ok_or(format!("{spell_name} is not one of your focus spells"))?;
+        if spell.is_cantrip() {
+            return Ok(());
+        }
+        let points = self.get_focus_points(spells);
+        if points <= 0 {
+            return Err(String::from("No focus points left, Refocus first"));
+        }
+        self.focus_points = points - 1;
+        Ok(())
+    }
+
+    /// Refocusing for 10 minutes restores one focus point
+    pub fn refocus(self: &mut Self, spells: &[Spell]) {
+        self.focus_points = std::cmp::min(self.get_focus_points(spells) + 1, self.get_max_focus_points(spells));
+    }
+
+    /// Daily preparations, restores every spell slot and focus point
+    pub fn rest(self: &mut Self, spells: &[Spell]) {
+        if let Some(spellcasting) = self.spellcasting.as_mut() {
+            spellcasting.rest();
+        }
+        self.focus_points = self.get_max_focus_points(spells);
+    }
+
+    /// Cantrips are always heightened to the highest rank the character can cast
+    pub fn get_cantrip_rank(self: &Self) -> i32 {
+        spells::get_max_spell_rank(self.level)
+    }
+
+    pub fn get_worn_armor(self: &Self) -> Option<&Gear> {
+        self.gear_list
+            .iter()
+            .find(|item| item.g_type == GearType::Armor && item.worn && item.armor_info.is_some())
+    }
+
+    /// Wears the armor with this name and takes off all other armor. An empty name takes off all armor
+    pub fn set_worn_armor(self: &mut Self, armor_name: &str) {
+        for item in self.gear_list.iter_mut().filter(|item| item.g_type == GearType::Armor) {
+            item.worn = item.name == armor_name;
+        }
+    }
+
+    pub fn get_condition_indx_from_name(self: &Self, condition_name: &str) -> Option<usize> {
+        self.conditions.iter().position(|cond| cond.name == condition_name)
+    }
+
+    pub fn add_condition(self: &mut Self, catalog: &[Condition], condition_name: &str, value: Option<i32>) -> Result<(), String> {
+        conditions::gain_condition(&mut self.conditions, catalog, condition_name, value)
+    }
+
+    pub fn remove_condition(self: &mut Self, catalog: &[Condition], condition_name: &str) {
+        conditions::lose_condition(&mut self.conditions, catalog, condition_name)
+    }
+
+    pub fn change_condition_value(self: &mut Self, catalog: &[Condition], condition_name: &str, offset: i32) {
+        conditions::change_condition_value(&mut self.conditions, catalog, condition_name, offset)
     }
 }
 
-impl From<&SimpleCharacter> for Character{
-    fn from(simp_char: &SimpleCharacter) -> Self {
+
+impl Character {
+    /// Builds the full character from its save format, looking up the attribute of every proficiency in the catalog
+    pub fn from_simple(simp_char: &SimpleCharacter, catalog: &StatCatalog) -> Result<Character, String> {
         let mut ret_val = Character {
             name: simp_char.name.clone(),
             hp_info: simp_char.hp_info.clone(),
-            horse_hp_info: simp_char.horse_hp_info.clone(),
+            companions: simp_char.companions.clone(),
             shield_info: simp_char.shield_info.clone(),
             level: simp_char.level,
+            size: simp_char.size.clone(),
+            speeds: simp_char.speeds.clone(),
             text: simp_char.text.clone(),
+            initiative_skill: simp_char.initiative_skill.clone(),
             attributes: Attributes::from(&((*simp_char).attributes)),
+            ancestry: simp_char.ancestry.clone(),
+            heritage: simp_char.heritage.clone(),
             background: simp_char.background.clone(),
             class: simp_char.class.clone(),
+            key_attribute: simp_char.key_attribute.clone(),
             proficiencies: vec![],
             feats: simp_char.feats.clone(),
             conditions: simp_char.conditions.clone(),
+            level_history: simp_char.level_history.clone(),
+            feat_records: simp_char.feat_records.clone(),
+            partial_boosts: simp_char.partial_boosts.clone(),
+            spellcasting: simp_char.spellcasting.clone(),
+            focus_points: simp_char.focus_points,
             tactics: simp_char.tactics.clone(),
             gear_list: simp_char.gear_list.clone(),
-            override_prof: simp_char.override_prof.clone(),
             abp_data: simp_char.abp_data.clone(),
-            flags: simp_char.flags.clone()
+            variants: simp_char.variants.clone().unwrap_or(VariantRules {
+                automatic_bonus_progression: simp_char.abp_data != AbpData::default()
+            }),
+            class_features: vec![],
+            unarmed_attacks: vec![],
+            rules: simp_char.rules.clone()
         };
 
+        if let (true, Some(horse_hp_info)) = (ret_val.companions.is_empty(), &simp_char.horse_hp_info) {
+            let mut horse = Companion::new("Horse", "Horse");
+            horse.hp_info = horse_hp_info.clone();
+            ret_val.companions.push(horse);
+        }
+        for (prof_name, attribute) in simp_char.override_prof.iter() {
+            ret_val.rules.push(RuleElement::AbilityOverride {selector: prof_name.to_lowercase(), attribute: attribute.clone()});
+        }
+        for (flag, _) in simp_char.flags.iter().filter(|(_, flag_v)| **flag_v) {
+            ret_val.rules.push(RuleElement::RollOption {option: flag.clone()});
+        }
+
         for skill_tuple in simp_char.proficiencies.clone() {
-            let attribute = Character::get_attribute_and_lore_flag_from_skill_name(skill_tuple.0.as_str(), &skill_tuple.1);
-            ret_val.proficiencies.push(CalculatedStat::new(skill_tuple.1, &attribute, skill_tuple.0.as_str(), skill_tuple.2))
+            ret_val.proficiencies.push(catalog.create_stat(skill_tuple.0.as_str(), skill_tuple.1, skill_tuple.2)?);
+        }
+        //stats added to the catalog after the character was saved, like Unarmed, start at their default proficiency
+        for stat in catalog.default_array() {
+            if !ret_val.proficiencies.iter().any(|prof| prof.name == stat.name && prof.p_type == stat.p_type) {
+                ret_val.proficiencies.push(stat);
+            }
         }
 
-        return ret_val;
+        Ok(ret_val)
+    }
+}
+
+impl Creature for Character {
+    fn get_level(&self) -> i32 {
+        self.level
+    }
+
+    /// "key" is the character's key attribute
+    fn get_attribute(&self, id: &str) -> Result<Attribute, String> {
+        if id == "key" {
+            return self.attributes.get_stat(&self.key_attribute);
+        }
+        self.attributes.get_stat(id)
+    }
+
+    fn get_base_speed(&self, movement: &MovementType) -> Option<i32> {
+        self.speeds.get_base(movement)
+    }
+
+    fn get_conditions(&self) -> &Vec<Condition> {
+        &self.conditions
+    }
+
+    /// Every rule element of the character, its feats, the gear it carries, its selected tactics and its conditions.
+    /// Armor only counts while worn
+    fn get_active_rules(&self) -> Vec<ActiveRule<'_>> {
+        let mut active = vec![];
+        active.extend(self.rules.iter().map(|rule| ActiveRule::new("Custom", rule)));
+        if self.variants.automatic_bonus_progression {
+            active.extend(self.abp_data.get_rules().into_iter().map(|rule| ActiveRule::derived("Automatic Bonus Progression", rule)));
+        }
+        for feature in self.class_features.iter().filter(|feature| feature.level <= self.level) {
+            active.extend(feature.rules.iter().map(|rule| ActiveRule::new(&feature.name, rule)));
+        }
+        for record in self.feat_records.iter() {
+            active.extend(record.rules.iter().map(|rule| ActiveRule::new(&record.feat, rule)));
+            for applied in record.applied.iter() {
+                match applied {
+                    AppliedAddition::Flag {flag} => active.push(ActiveRule::derived(&record.feat, RuleElement::RollOption {option: flag.clone()})),
+                    AppliedAddition::AttributeOverride {name, attribute} => active.push(ActiveRule::derived(
+                        &record.feat,
+                        RuleElement::AbilityOverride {selector: name.to_lowercase(), attribute: attribute.clone()}
+                    )),
+                    _ => {},
+                }
+            }
+        }
+        for item in self.gear_list.iter().filter(|item| item.g_type != GearType::Armor || item.worn) {
+            active.extend(item.rules.iter().map(|rule| ActiveRule::new(&item.name, rule)));
+        }
+        for tactic in self.tactics.iter().filter(|tactic| tactic.selected) {
+            active.extend(tactic.rules.iter().map(|rule| ActiveRule::new(&tactic.name, rule)));
+        }
+        for cond in self.conditions.iter() {
+            active.extend(cond.rules.iter().map(|rule| ActiveRule::new(&cond.name, rule)));
+        }
+        active
+    }
+
+    /// Armor penalties, rune bonuses of the weapon a stat selects and a raised shield
+    fn get_extra_modifiers(&self, selectors: &[String]) -> Vec<Modifier> {
+        let has_selector = |selector: &str| selectors.iter().any(|s| s == selector);
+        let mut modifiers = vec![];
+        if let Some(armor) = self.get_worn_armor() {
+            let armor_info = armor.armor_info.as_ref().expect("Worn armor is expected to have armor info");
+            let str_value = self.attributes.get_stat_val("str").expect("Armor expects a str attribute to be set");
+            let check_penalty = armor_info.get_check_penalty(str_value);
+            if check_penalty != 0 && has_selector("skill") && (has_selector("str") || has_selector("dex")) {
+                modifiers.push(Modifier::new(&format!("{0} check penalty", armor.name), ModifierType::Untyped, -check_penalty));
+            }
+            let speed_penalty = armor_info.get_speed_penalty(str_value);
+            if speed_penalty != 0 && has_selector("speed") {
+                modifiers.push(Modifier::new(&armor.name, ModifierType::Untyped, -speed_penalty));
+            }
+            if armor_info.runes.resilient != 0 && !self.variants.automatic_bonus_progression && has_selector("save") {
+                modifiers.push(Modifier::new(&format!("{0} resilient", armor.name), ModifierType::Item, armor_info.runes.resilient));
+            }
+        }
+        let granted_items = rules::get_granted_items(&self.get_active_rules());
+        for weapon in self.gear_list.iter().chain(granted_items.iter()).filter(|item| item.g_type == GearType::Weapon) {
+            if !has_selector(&format!("{0}-attack", weapon.name.to_lowercase())) {
+                continue;
+            }
+            let potency = weapon.weap_info.as_ref().map(|info| info.runes.potency).unwrap_or(0);
+            if potency != 0 && !self.variants.automatic_bonus_progression {
+                modifiers.push(Modifier::new(&format!("{0} potency", weapon.name), ModifierType::Item, potency));
+            }
+            if weapon.has_trait("Improvised") {
+                modifiers.push(Modifier::new("Improvised", ModifierType::Item, -IMPROVISED_PENALTY));
+            }
+        }
+        if has_selector("ac") && self.shield_info.raised {
+            modifiers.push(Modifier::new("Raised Shield", ModifierType::Circumstance, 2));
+        }
+        modifiers
     }
 }
 
@@ -258,21 +1249,35 @@ impl From<Character> for SimpleCharacter{
         let mut ret_val = SimpleCharacter {
             name: ref_char.name.clone(),
             hp_info: ref_char.hp_info.clone(),
-            horse_hp_info: ref_char.horse_hp_info.clone(),
+            companions: ref_char.companions.clone(),
+            horse_hp_info: None,
             shield_info: ref_char.shield_info.clone(),
             level: ref_char.level,
+            size: ref_char.size.clone(),
+            speeds: ref_char.speeds.clone(),
             text: ref_char.text,
+            initiative_skill: ref_char.initiative_skill,
             attributes: ref_char.attributes.as_number_vec(),
+            ancestry: ref_char.ancestry.clone(),
+            heritage: ref_char.heritage.clone(),
             background: ref_char.background.clone(),
             class: ref_char.class.clone(),
+            key_attribute: ref_char.key_attribute.clone(),
             proficiencies: vec![],
             feats: ref_char.feats.clone(),
             conditions: ref_char.conditions.clone(),
+            level_history: ref_char.level_history.clone(),
+            feat_records: ref_char.feat_records.clone(),
+            partial_boosts: ref_char.partial_boosts.clone(),
+            spellcasting: ref_char.spellcasting.clone(),
+            focus_points: ref_char.focus_points,
             tactics: ref_char.tactics.clone(),
             gear_list: ref_char.gear_list.clone(),
-            override_prof: ref_char.override_prof.clone(),
+            override_prof: HashMap::new(),
             abp_data: ref_char.abp_data.clone(),
-            flags: ref_char.flags.clone()
+            variants: Some(ref_char.variants.clone()),
+            flags: HashMap::new(),
+            rules: ref_char.rules.clone()
         };
 
         ret_val.proficiencies.extend(ref_char.proficiencies.into_iter().map(|s: CalculatedStat| return (s.name, s.p_type, s.proficiency)));
@@ -285,21 +1290,35 @@ impl From<&Character> for SimpleCharacter{
         let mut ret_val = SimpleCharacter {
             name: ref_char.name.clone(),
             hp_info: ref_char.hp_info.clone(),
-            horse_hp_info: ref_char.horse_hp_info.clone(),
+            companions: ref_char.companions.clone(),
+            horse_hp_info: None,
             shield_info: ref_char.shield_info.clone(),
             level: ref_char.level,
+            size: ref_char.size.clone(),
+            speeds: ref_char.speeds.clone(),
             text: ref_char.text.clone(),
+            initiative_skill: ref_char.initiative_skill.clone(),
             attributes: ref_char.attributes.as_number_vec(),
+            ancestry: ref_char.ancestry.clone(),
+            heritage: ref_char.heritage.clone(),
             background: ref_char.background.clone(),
             class: ref_char.class.clone(),
+            key_attribute: ref_char.key_attribute.clone(),
             proficiencies: vec![],
             feats: ref_char.feats.clone(),
             conditions: ref_char.conditions.clone(),
+            level_history: ref_char.level_history.clone(),
+            feat_records: ref_char.feat_records.clone(),
+            partial_boosts: ref_char.partial_boosts.clone(),
+            spellcasting: ref_char.spellcasting.clone(),
+            focus_points: ref_char.focus_points,
             tactics: ref_char.tactics.clone(),
             gear_list: ref_char.gear_list.clone(),
-            override_prof: ref_char.override_prof.clone(),
+            override_prof: HashMap::new(),
             abp_data: ref_char.abp_data.clone(),
-            flags: ref_char.flags.clone()
+            variants: Some(ref_char.variants.clone()),
+            flags: HashMap::new(),
+            rules: ref_char.rules.clone()
         };
 
         ret_val.proficiencies.extend(ref_char.proficiencies.clone().into_iter().map(|s: CalculatedStat| return (s.name, s.p_type, s.proficiency)));
@@ -307,3 +1326,57 @@ impl From<&Character> for SimpleCharacter{
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> StatCatalog {
+        serde_json::from_str(include_str!("../../resources/skills.json")).expect("skills.json should parse")
+    }
+
+    fn classes() -> Vec<ClassData> {
+        serde_json::from_str(include_str!("../../resources/classes.json")).expect("classes.json should parse")
+    }
+
+    fn feat_map() -> HashMap<String, Feat> {
+        let feats: Vec<Feat> = serde_json::from_str(include_str!("../../resources/feats.json")).expect("feats.json should parse");
+        feats.into_iter().map(|feat| (feat.name.clone(), feat)).collect()
+    }
+
+    fn saved_character() -> Character {
+        let simple: SimpleCharacter = serde_json::from_str(include_str!("../../saves/char.json")).expect("char.json should parse");
+        let mut character = Character::from_simple(&simple, &catalog()).unwrap();
+        character.sync_feat_additions(&feat_map(), &catalog()).unwrap();
+        character
+    }
+
+    #[test]
+    fn level_up_then_down_restores_the_character() {
+        let mut character = saved_character();
+        let original = character.clone();
+        let feat_choice = LevelUpChoices {feats: vec![String::from("Combat Assessment")], ..Default::default()};
+        character.level_up(&feat_choice, &classes(), &feat_map(), &catalog()).unwrap();
+        let before_boosts = character.clone();
+        let boost_choice = LevelUpChoices {
+            boosts: ["str", "dex", "con", "int"].into_iter().map(String::from).collect(),
+            skill_increase: String::from("Society"),
+            feats: vec![],
+        };
+        character.level_up(&boost_choice, &classes(), &feat_map(), &catalog()).unwrap();
+        assert_eq!(character.level, original.level + 2);
+        character.level_down().unwrap();
+        assert_eq!(character, before_boosts);
+        character.level_down().unwrap();
+        assert_eq!(character, original);
+    }
+
+    #[test]
+    fn failed_level_up_leaves_the_character_unchanged() {
+        let mut character = saved_character();
+        let original = character.clone();
+        let choice = LevelUpChoices {feats: vec![String::from("Combat Assessment"), String::from("Not A Feat")], ..Default::default()};
+        assert!(character.level_up(&choice, &classes(), &feat_map(), &catalog()).is_err());
+        assert_eq!(character, original);
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use super::{feats::FeatType, proficiency::ProficiencyLevel, rules::RuleElement, spells::ClassSpellcasting, stats::ProficiencyType};
+
+/// A proficiency some origin or class gives the character from level 1
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProficiencyGrant {
+    pub name: String,
+    pub p_type: ProficiencyType,
+    pub proficiency: ProficiencyLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClassFeature {
+    pub level: i32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Proficiencies the feature raises when the character reaches its level
+    #[serde(default)]
+    pub proficiencies: Vec<ProficiencyGrant>,
+    /// Active once the character reaches the feature's level
+    #[serde(default)]
+    pub rules: Vec<RuleElement>,
+}
+
+fn every_other_level(start: i32) -> Vec<i32> {
+    (start..=20).step_by(2).collect()
+}
+
+fn default_skill_increase_levels() -> Vec<i32> {
+    every_other_level(3)
+}
+
+fn default_class_feat_levels() -> Vec<i32> {
+    every_other_level(2)
+}
+
+fn default_skill_feat_levels() -> Vec<i32> {
+    every_other_level(2)
+}
+
+fn default_general_feat_levels() -> Vec<i32> {
+    vec![3, 7, 11, 15, 19]
+}
+
+fn default_ancestry_feat_levels() -> Vec<i32> {
+    vec![5, 9, 13, 17]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClassData {
+    pub name: String,
+    /// Attribute ids the class lets you choose as key attribute
+    pub key_attributes: Vec<String>,
+    /// Hit points gained per level, before the constitution modifier
+    #[serde(default)]
+    pub hp: i32,
+    #[serde(default)]
+    pub trained_skills: Vec<String>,
+    /// Number of additional skills the player chooses, the intelligence modifier is added on top
+    #[serde(default)]
+    pub additional_skills: i32,
+    /// Perception, saves, armor, weapons and class DC at level 1
+    #[serde(default)]
+    pub initial_proficiencies: Vec<ProficiencyGrant>,
+    #[serde(default)]
+    pub features: Vec<ClassFeature>,
+    /// Levels after the first that grant a skill increase or a feat of the given kind
+    #[serde(default = "default_skill_increase_levels")]
+    pub skill_increase_levels: Vec<i32>,
+    #[serde(default = "default_class_feat_levels")]
+    pub class_feat_levels: Vec<i32>,
+    #[serde(default = "default_skill_feat_levels")]
+    pub skill_feat_levels: Vec<i32>,
+    #[serde(default = "default_general_feat_levels")]
+    pub general_feat_levels: Vec<i32>,
+    #[serde(default = "default_ancestry_feat_levels")]
+    pub ancestry_feat_levels: Vec<i32>,
+    #[serde(default)]
+    pub spellcasting: Option<ClassSpellcasting>,
+}
+
+impl ClassData {
+    pub fn get_features_at_level(&self, level: i32) -> Vec<&ClassFeature> {
+        self.features.iter().filter(|feature| feature.level == level).collect()
+    }
+
+    pub fn has_skill_increase(&self, level: i32) -> bool {
+        self.skill_increase_levels.contains(&level)
+    }
+
+    /// The kind of every feat the character gains at this level
+    pub fn get_feat_slots(&self, level: i32) -> Vec<FeatType> {
+        let mut slots = vec![];
+        if self.ancestry_feat_levels.contains(&level) {
+            slots.push(FeatType::Ancestry);
+        }
+        if self.class_feat_levels.contains(&level) {
+            slots.push(FeatType::Class);
+        }
+        if self.skill_feat_levels.contains(&level) {
+            slots.push(FeatType::Skill);
+        }
+        if self.general_feat_levels.contains(&level) {
+            slots.push(FeatType::General);
+        }
+        slots
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+
+use super::{classes::ProficiencyGrant, conditions::{self, Condition}, creature::Creature, hp::HpInfo, modifiers::StatBreakdown, movement::{MovementType, Size, Speeds}, proficiency::ProficiencyLevel, rules::{ActiveRule, RuleElement}, stat_catalog::StatCatalog, stats::{Attribute, Attributes, CalculatedStat, ProficiencyType}, traits};
+
+/// Hit points an animal companion gains per level, before its constitution modifier
+pub const COMPANION_HP_PER_LEVEL: i32 = 6;
+pub const YOUNG_ADVANCEMENT: &str = "Young";
+
+fn default_advancement() -> String {
+    String::from(YOUNG_ADVANCEMENT)
+}
+
+fn default_hp_per_level() -> i32 {
+    COMPANION_HP_PER_LEVEL
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompanionAttack {
+    pub name: String,
+    /// Size of the damage die, the advancement decides how many dice
+    pub damage: i32,
+    pub d_type: String,
+    #[serde(default)]
+    pub traits: Vec<String>,
+}
+
+/// A kind of companion like a horse or a wolf, loaded from resources/companions.json
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompanionTypeData {
+    pub name: String,
+    #[serde(default)]
+    pub size: Size,
+    pub hp: i32,
+    #[serde(default = "default_hp_per_level")]
+    pub hp_per_level: i32,
+    /// Advancement a new companion of this type starts with, familiars and summons use their own
+    #[serde(default = "default_advancement")]
+    pub default_advancement: String,
+    #[serde(default)]
+    pub traits: Vec<String>,
+    /// Attribute modifiers in the order str, dex, con, int, wis, cha
+    pub attributes: Vec<i32>,
+    #[serde(default)]
+    pub speeds: Speeds,
+    #[serde(default)]
+    pub attacks: Vec<CompanionAttack>,
+    /// Skill the companion is trained in on top of Acrobatics and Athletics
+    #[serde(default)]
+    pub skill: String,
+    #[serde(default)]
+    pub support_benefit: String,
+    #[serde(default)]
+    pub advanced_maneuver: String,
+    #[serde(default)]
+    pub image: String,
+    #[serde(default)]
+    pub rules: Vec<RuleElement>,
+}
+
+/// A step like Mature or Savage, applied on top of every advancement it requires
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompanionAdvancement {
+    pub name: String,
+    #[serde(default)]
+    pub requires: Option<String>,
+    /// Added to the attribute modifiers in the order str, dex, con, int, wis, cha
+    #[serde(default)]
+    pub attribute_increases: Vec<i32>,
+    #[serde(default)]
+    pub size_increase: i32,
+    #[serde(default)]
+    pub speed_increase: i32,
+    /// Damage dice of the attacks, the highest advancement that sets them wins
+    #[serde(default)]
+    pub damage_dice: i32,
+    #[serde(default)]
+    pub extra_damage: i32,
+    /// Raised proficiencies, Skill grants named "type" use the skill of the companion type
+    #[serde(default)]
+    pub proficiencies: Vec<ProficiencyGrant>,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompanionCatalog {
+    pub types: Vec<CompanionTypeData>,
+    pub advancements: Vec<CompanionAdvancement>,
+}
+
+impl CompanionCatalog {
+    pub fn get_type(&self, name: &str) -> Result<&CompanionTypeData, String> {
+        self.types.iter().find(|c_type| c_type.name == name).ok_or(format!("The companion type {name} does not exist"))
+    }
+
+    pub fn get_advancement(&self, name: &str) -> Result<&CompanionAdvancement, String> {
+        self.advancements.iter().find(|advancement| advancement.name == name).ok_or(format!("The companion advancement {name} does not exist"))
+    }
+
+    /// The advancement with every advancement it requires, starting with the first one
+    pub fn get_advancement_chain(&self, name: &str) -> Result<Vec<&CompanionAdvancement>, String> {
+        let mut chain = vec![];
+        let mut next = Some(String::from(name));
+        while let Some(name) = next {
+            let advancement = self.get_advancement(&name)?;
+            if chain.iter().any(|chained: &&CompanionAdvancement| chained.name == advancement.name) {
+                return Err(format!("The companion advancement {name} requires itself"));
+            }
+            next = advancement.requires.clone();
+            chain.push(advancement);
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+}
+
+/// An attack of the companion with its bonus and damage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompanionStrike {
+    pub name: String,
+    pub breakdown: StatBreakdown,
+    pub damage: String,
+    pub traits: Vec<String>,
+}
+
+/// A companion together with what its type, advancement and the character's level give it, so it can use the stat math of the character
+pub struct CompanionStats<'a> {
+    pub companion: &'a Companion,
+    pub companion_type: &'a CompanionTypeData,
+    pub level: i32,
+    pub attributes: Attributes,
+    pub speeds: Speeds,
+}
+
+impl Creature for CompanionStats<'_> {
+    fn get_level(&self) -> i32 {
+        self.level
+    }
+
+    fn get_attribute(&self, id: &str) -> Result<Attribute, String> {
+        self.attributes.get_stat(id)
+    }
+
+    fn get_base_speed(&self, movement: &MovementType) -> Option<i32> {
+        self.speeds.get_base(movement)
+    }
+
+    fn get_conditions(&self) -> &Vec<Condition> {
+        &self.companion.conditions
+    }
+
+    /// Rule elements of the companion type and of the companion's conditions
+    fn get_active_rules(&self) -> Vec<ActiveRule<'_>> {
+        let mut active: Vec<ActiveRule> = self.companion_type.rules.iter().map(|rule| ActiveRule::new(&self.companion_type.name, rule)).collect();
+        for cond in self.companion.conditions.iter() {
+            active.extend(cond.rules.iter().map(|rule| ActiveRule::new(&cond.name, rule)));
+        }
+        active
+    }
+}
+
+/// A creature fighting alongside the character. Everything but its name, type, advancement, hp and conditions comes from the companion catalog
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Companion {
+    pub name: String,
+    pub companion_type: String,
+    #[serde(default = "default_advancement")]
+    pub advancement: String,
+    pub hp_info: HpInfo,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+}
+
+impl Companion {
+    pub fn new(name: &str, companion_type: &str) -> Companion {
+        Companion {
+            name: String::from(name),
+            companion_type: String::from(companion_type),
+            advancement: default_advancement(),
+            hp_info: HpInfo::new(0, 0, 1, 0),
+            conditions: vec![],
+        }
+    }
+
+    pub fn get_attributes(&self, catalog: &CompanionCatalog) -> Result<Attributes, String> {
+        let mut values = catalog.get_type(&self.companion_type)?.attributes.clone();
+        values.resize(6, 0);
+        for advancement in catalog.get_advancement_chain(&self.advancement)? {
+            for (value, increase) in values.iter_mut().zip(advancement.attribute_increases.iter()) {
+                *value += increase;
+            }
+        }
+        Ok(Attributes::from(values))
+    }
+
+    pub fn get_size(&self, catalog: &CompanionCatalog) -> Result<Size, String> {
+        let sizes = [Size::Tiny, Size::Small, Size::Medium, Size::Large, Size::Huge, Size::Gargantuan];
+        let base = catalog.get_type(&self.companion_type)?.size.clone();
+        let base_indx = sizes.iter().position(|size| *size == base).expect("Every size is listed");
+        let increase: i32 = catalog.get_advancement_chain(&self.advancement)?.iter().map(|advancement| advancement.size_increase).sum();
+        let indx = std::cmp::min(base_indx as i32 + increase, sizes.len() as i32 - 1);
+        Ok(sizes[std::cmp::max(indx, 0) as usize].clone())
+    }
+
+    /// Speeds of the type, advancements raise every speed the companion has
+    pub fn get_speeds(&self, catalog: &CompanionCatalog) -> Result<Speeds, String> {
+        let mut speeds = catalog.get_type(&self.companion_type)?.speeds.clone();
+        let increase: i32 = catalog.get_advancement_chain(&self.advancement)?.iter().map(|advancement| advancement.speed_increase).sum();
+        speeds.land += increase;
+        for speed in [&mut speeds.swim, &mut speeds.climb, &mut speeds.fly, &mut speeds.burrow] {
+            if let Some(value) = speed.as_mut() {
+                *value += increase;
+            }
+        }
+        Ok(speeds)
+    }
+
+    /// Every proficiency the advancements grant, the highest rank of each
+    pub fn get_proficiencies(&self, catalog: &CompanionCatalog, stat_catalog: &StatCatalog) -> Result<Vec<CalculatedStat>, String> {
+        let companion_type = catalog.get_type(&self.companion_type)?;
+        let mut proficiencies: Vec<CalculatedStat> = vec![];
+        for advancement in catalog.get_advancement_chain(&self.advancement)? {
+            for grant in advancement.proficiencies.iter() {
+                let name = if grant.p_type == ProficiencyType::Skill && grant.name == "type" {&companion_type.skill} else {&grant.name};
+                if name.is_empty() {
+                    continue;
+                }
+                match proficiencies.iter_mut().find(|prof| prof.name == *name) {
+                    Some(prof) => prof.proficiency = std::cmp::max(prof.proficiency.clone(), grant.proficiency.clone()),
+                    None => proficiencies.push(stat_catalog.create_stat(name, grant.p_type.clone(), grant.proficiency.clone())?),
+                }
+            }
+        }
+        Ok(proficiencies)
+    }
+
+    fn get_proficiency(&self, name: &str, p_type: ProficiencyType, catalog: &CompanionCatalog, stat_catalog: &StatCatalog) -> Result<CalculatedStat, String> {
+        match self.get_proficiencies(catalog, stat_catalog)?.into_iter().find(|prof| prof.name == name) {
+            Some(prof) => Ok(prof),
+            None => stat_catalog.create_stat(name, p_type, ProficiencyLevel::Untrained),
+        }
+    }
+
+    pub fn get_stats<'a>(&'a self, level: i32, catalog: &'a CompanionCatalog) -> Result<CompanionStats<'a>, String> {
+        Ok(CompanionStats {
+            companion: self,
+            companion_type: catalog.get_type(&self.companion_type)?,
+            level,
+            attributes: self.get_attributes(catalog)?,
+            speeds: self.get_speeds(catalog)?,
+        })
+    }
+
+    pub fn calculate_stat_breakdown(&self, stat: &CalculatedStat, level: i32, catalog: &CompanionCatalog) -> Result<StatBreakdown, String> {
+        self.get_stats(level, catalog)?.calculate_stat_breakdown(stat, &[])
+    }
+
+    pub fn calculate_ac_breakdown(&self, level: i32, catalog: &CompanionCatalog, stat_catalog: &StatCatalog) -> Result<StatBreakdown, String> {
+        let unarmored = self.get_proficiency("Unarmored", ProficiencyType::Armor, catalog, stat_catalog)?;
+        self.get_stats(level, catalog)?.calculate_armor_class(&unarmored.name, &unarmored.proficiency, None, None)
+    }
+
+    /// Attacks use strength, finesse attacks the higher of strength and dexterity
+    pub fn get_strikes(&self, level: i32, catalog: &CompanionCatalog, stat_catalog: &StatCatalog) -> Result<Vec<CompanionStrike>, String> {
+        let stats = self.get_stats(level, catalog)?;
+        let chain = catalog.get_advancement_chain(&self.advancement)?;
+        let dice = chain.iter().map(|advancement| advancement.damage_dice).max().unwrap_or(0).max(1);
+        let extra_damage: i32 = chain.iter().map(|advancement| advancement.extra_damage).sum();
+        let str_value = stats.attributes.get_stat_val("str")?;
+        let dex_value = stats.attributes.get_stat_val("dex")?;
+        let mut stat = self.get_proficiency("Unarmed", ProficiencyType::Weapon, catalog, stat_catalog)?;
+        stats.companion_type.attacks.iter().map(|attack| {
+            stat.attribute = String::from(if traits::has_trait(&attack.traits, "Finesse") && dex_value > str_value {"dex"} else {"str"});
+            let breakdown = stats.calculate_stat_breakdown(&stat, &[])?;
+            let damage_bonus = str_value + extra_damage;
+            let bonus_text = if damage_bonus != 0 {format!(" {damage_bonus:+}")} else {String::from("")};
+            Ok(CompanionStrike {
+                name: attack.name.clone(),
+                breakdown,
+                damage: format!("{dice}d{0}{bonus_text} {1}", attack.damage, attack.d_type),
+                traits: attack.traits.clone(),
+            })
+        }).collect()
+    }
+
+    pub fn calculate_speed_breakdown(&self, movement: &MovementType, level: i32, catalog: &CompanionCatalog) -> Result<Option<StatBreakdown>, String> {
+        Ok(self.get_stats(level, catalog)?.calculate_speed_breakdown(movement))
+    }
+
+    /// Recalculates the maximum hit points for the companion's type, advancement and level
+    pub fn sync(&mut self, level: i32, catalog: &CompanionCatalog) -> Result<(), String> {
+        let companion_type = catalog.get_type(&self.companion_type)?;
+        let con = self.get_attributes(catalog)?.get_stat_val("con")?;
+        self.hp_info.set_sources(companion_type.hp, companion_type.hp_per_level);
+        self.hp_info.calculate_max_hp(level, con);
+        Ok(())
+    }
+
+    pub fn set_advancement(&mut self, advancement: &str, level: i32, catalog: &CompanionCatalog) -> Result<(), String> {
+        catalog.get_advancement_chain(advancement)?;
+        self.advancement = String::from(advancement);
+        self.sync(level, catalog)
+    }
+
+    pub fn add_condition(&mut self, catalog: &[Condition], condition_name: &str, value: Option<i32>) -> Result<(), String> {
+        conditions::gain_condition(&mut self.conditions, catalog, condition_name, value)
+    }
+
+    pub fn remove_condition(&mut self, catalog: &[Condition], condition_name: &str) {
+        conditions::lose_condition(&mut self.conditions, catalog, condition_name)
+    }
+
+    pub fn change_condition_value(&mut self, catalog: &[Condition], condition_name: &str, offset: i32) {
+        conditions::change_condition_value(&mut self.conditions, catalog, condition_name, offset)
+    }
+}
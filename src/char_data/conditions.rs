@@ -1,4 +1,6 @@
 use serde::{Serialize, Deserialize};
+use leptos::logging::log;
+use super::{modifiers::{Modifier, ModifierType}, rules::RuleElement};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Condition {
@@ -18,6 +20,15 @@ pub struct Condition {
     pub increase_on_gain_by: Option<String>,
     #[serde(default)]
     pub added_on_gain: Vec<String>,
+    pub value: Option<i32>,
+    /// Names of the active conditions that forced this one onto the character
+    #[serde(default)]
+    pub implied_by: Vec<String>,
+    /// Set when the condition was gained on its own and not only through another condition
+    #[serde(default)]
+    pub explicit: bool,
+    #[serde(default)]
+    pub rules: Vec<RuleElement>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -41,3 +52,296 @@ pub struct Amount {
     pub penalty_type: Option<String>,
 }
 
+impl Condition {
+    pub fn get_value(&self) -> i32 {
+        self.value.unwrap_or(0)
+    }
+
+    /// Returns a negative modifier for every penalty of this condition that matches one of the selectors.
+    /// Each penalty entry is only counted once, even if several of its selectors match.
+    pub fn get_modifiers(&self, selectors: &[String]) -> Vec<Modifier> {
+        let source = match self.value {
+            Some(val) => format!("{0} {val}", self.name),
+            None => self.name.clone(),
+        };
+        self.penalty
+            .iter()
+            .filter(|pen| pen.matches(selectors) && !pen.is_set())
+            .map(|pen| Modifier::new(&source, pen.get_modifier_type(), -pen.get_amount(self.get_value())))
+            .filter(|modifier| modifier.value != 0)
+            .collect()
+    }
+
+    /// Values that matching "set" penalties force the stat to, e.g. Immobilized setting the speed to 0
+    pub fn get_set_values(&self, selectors: &[String]) -> Vec<i32> {
+        self.penalty
+            .iter()
+            .filter(|pen| pen.matches(selectors) && pen.is_set())
+            .map(|pen| pen.get_amount(self.get_value()))
+            .collect()
+    }
+}
+
+impl Penalty {
+    pub fn matches(&self, selectors: &[String]) -> bool {
+        self.selector.iter().any(|sel| selectors.contains(&sel.to_lowercase()))
+    }
+
+    pub fn is_set(&self) -> bool {
+        match &self.amount {
+            Some(amount) => amount.calc_type == "set",
+            None => false,
+        }
+    }
+
+    /// "fixed" and "set" penalties use the value set in the data, "calculated" ones (or ones without an amount) use the condition value
+    pub fn get_amount(&self, condition_value: i32) -> i32 {
+        match &self.amount {
+            Some(amount) => match amount.calc_type.as_str() {
+                "fixed" | "set" => amount.value.unwrap_or(0),
+                _ => condition_value,
+            },
+            None => condition_value,
+        }
+    }
+
+    /// Conditions give status penalties unless the data says otherwise
+    pub fn get_modifier_type(&self) -> ModifierType {
+        match &self.amount {
+            Some(Amount { penalty_type: Some(p_type), .. }) => ModifierType::from(p_type.as_str()),
+            _ => ModifierType::Status,
+        }
+    }
+}
+
+
+/// Adds a condition to the active list and applies its side effects:
+/// overridden conditions are removed, `increase_on_gain_by` raises the new value,
+/// `forced_conditions` are added as implied by this condition and `added_on_gain` conditions are gained on their own.
+pub fn gain_condition(active: &mut Vec<Condition>, catalog: &[Condition], name: &str, value: Option<i32>) -> Result<(), String> {
+    apply_gain(active, catalog, name, value, None, &mut vec![])
+}
+
+fn apply_gain(active: &mut Vec<Condition>, catalog: &[Condition], name: &str, value: Option<i32>, source: Option<&str>, stack: &mut Vec<String>) -> Result<(), String> {
+    if stack.iter().any(|visited| visited == name) {
+        log!("Condition cycle detected while adding {name}, skipping");
+        return Ok(());
+    }
+    let definition = catalog
+        .iter()
+        .find(|cond| cond.name == name)
+        .ok_or(format!("Could not find a condition with name {name}"))?;
+    if let Some(overriding) = active.iter().find(|cond| cond.override_field.iter().any(|o| o == name)) {
+        log!("{name} is overridden by {0} and is not added", overriding.name);
+        return Ok(());
+    }
+    stack.push(String::from(name));
+    for overridden in definition.override_field.iter() {
+        remove_condition_fully(active, catalog, overridden);
+    }
+    let new_value = if definition.has_value {Some(std::cmp::max(value.unwrap_or(1), 1))} else {None};
+    match active.iter().position(|cond| cond.name == name) {
+        Some(indx) => {
+            let existing = &mut active[indx];
+            if let (Some(old_value), Some(new_value)) = (existing.value, new_value) {
+                existing.value = Some(std::cmp::max(old_value, new_value));
+            }
+            match source {
+                Some(source_name) => {
+                    if !existing.implied_by.iter().any(|s| s == source_name) {
+                        existing.implied_by.push(String::from(source_name));
+                    }
+                },
+                None => existing.explicit = true,
+            }
+        },
+        None => {
+            let increase = match &definition.increase_on_gain_by {
+                Some(increaser) => active.iter().find(|cond| &cond.name == increaser).map(|cond| cond.get_value()).unwrap_or(0),
+                None => 0,
+            };
+            let mut new_condition = definition.clone();
+            new_condition.value = new_value.map(|val| val + increase);
+            new_condition.explicit = source.is_none();
+            new_condition.implied_by = source.into_iter().map(String::from).collect();
+            active.push(new_condition);
+            for forced in definition.forced_conditions.iter() {
+                apply_gain(active, catalog, &forced.name, forced.value, Some(name), stack)?;
+            }
+            for added in definition.added_on_gain.iter() {
+                apply_gain(active, catalog, added, None, None, stack)?;
+            }
+        }
+    }
+    stack.pop();
+    Ok(())
+}
+
+/// Removes a condition the character gained on its own.
+/// If it is still forced by another active condition it stays active until that one is removed.
+pub fn lose_condition(active: &mut Vec<Condition>, catalog: &[Condition], name: &str) {
+    let Some(indx) = active.iter().position(|cond| cond.name == name) else {
+        return;
+    };
+    if !active[indx].implied_by.is_empty() {
+        active[indx].explicit = false;
+        log!("{name} is still implied by {0:?}", active[indx].implied_by);
+        return;
+    }
+    remove_condition_fully(active, catalog, name);
+}
+
+/// Gains a condition that something outside the condition list grants, e.g. a feat. The source keeps it active until revoked
+pub fn grant_condition(active: &mut Vec<Condition>, catalog: &[Condition], name: &str, value: Option<i32>, source: &str) -> Result<(), String> {
+    apply_gain(active, catalog, name, value, Some(source), &mut vec![])
+}
+
+/// Takes away what a source implied, removing every condition nothing else keeps active.
+/// Only the listed conditions are released, None releases all of them
+pub fn revoke_source(active: &mut Vec<Condition>, catalog: &[Condition], source: &str, only: Option<&str>) {
+    let mut orphaned = vec![];
+    for cond in active.iter_mut() {
+        if only.is_some_and(|name| name != cond.name) {
+            continue;
+        }
+        if cond.implied_by.iter().any(|s| s == source) {
+            cond.implied_by.retain(|s| s != source);
+            if cond.implied_by.is_empty() && !cond.explicit {
+                orphaned.push(cond.name.clone());
+            }
+        }
+    }
+    for orphan in orphaned {
+        remove_condition_fully(active, catalog, &orphan);
+    }
+}
+
+/// Removes the condition, every condition only it implied and then applies its `added_on_remove` list
+fn remove_condition_fully(active: &mut Vec<Condition>, catalog: &[Condition], name: &str) {
+    let Some(indx) = active.iter().position(|cond| cond.name == name) else {
+        return;
+    };
+    let removed = active.remove(indx);
+    revoke_source(active, catalog, &removed.name, None);
+    for added in removed.added_on_remove.iter() {
+        match active.iter_mut().find(|cond| &cond.name == added) {
+            Some(existing) if existing.has_value => {
+                existing.value = Some(existing.get_value() + 1);
+                existing.explicit = true;
+            },
+            _ => {
+                if let Err(err) = gain_condition(active, catalog, added, None) {
+                    log!("{err}");
+                }
+            }
+        }
+    }
+}
+
+/// Changes the value of a valued condition, losing it once the value drops to 0.
+/// Conditions without a value are lost on any negative offset.
+pub fn change_condition_value(active: &mut Vec<Condition>, catalog: &[Condition], name: &str, offset: i32) {
+    let Some(indx) = active.iter().position(|cond| cond.name == name) else {
+        return;
+    };
+    if !active[indx].has_value {
+        if offset < 0 {
+            lose_condition(active, catalog, name);
+        }
+        return;
+    }
+    let new_value = active[indx].get_value() + offset;
+    if new_value <= 0 {
+        lose_condition(active, catalog, name);
+    }
+    else {
+        active[indx].value = Some(new_value);
+    }
+}
+
+/// Lists every chain of conditions that lead to this one, e.g. "Off-Guard <- Prone <- Unconcious <- Dying"
+pub fn explain_condition(active: &[Condition], name: &str) -> Vec<String> {
+    let mut chains = vec![];
+    collect_implication_chains(active, name, &mut vec![String::from(name)], &mut chains);
+    chains
+}
+
+fn collect_implication_chains(active: &[Condition], name: &str, chain: &mut Vec<String>, chains: &mut Vec<String>) {
+    let Some(cond) = active.iter().find(|cond| cond.name == name) else {
+        return;
+    };
+    if cond.explicit || cond.implied_by.is_empty() {
+        chains.push(chain.join(" <- "));
+    }
+    for source in cond.implied_by.iter() {
+        if chain.contains(source) {
+            continue;
+        }
+        chain.push(source.clone());
+        collect_implication_chains(active, source, chain, chains);
+        chain.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> Vec<Condition> {
+        serde_json::from_str(include_str!("../../resources/conditions.json")).expect("conditions.json should parse")
+    }
+
+    fn get<'a>(active: &'a [Condition], name: &str) -> Option<&'a Condition> {
+        active.iter().find(|cond| cond.name == name)
+    }
+
+    #[test]
+    fn dying_increases_by_wounded() {
+        let catalog = catalog();
+        let mut active = vec![];
+        gain_condition(&mut active, &catalog, "Wounded", Some(1)).unwrap();
+        gain_condition(&mut active, &catalog, "Dying", Some(1)).unwrap();
+        assert_eq!(get(&active, "Dying").and_then(|cond| cond.value), Some(2));
+        assert!(get(&active, "Unconcious").is_some());
+    }
+
+    #[test]
+    fn removing_dying_leaves_wounded_and_prone() {
+        let catalog = catalog();
+        let mut active = vec![];
+        gain_condition(&mut active, &catalog, "Dying", Some(1)).unwrap();
+        lose_condition(&mut active, &catalog, "Dying");
+        assert!(get(&active, "Dying").is_none());
+        assert!(get(&active, "Unconcious").is_none());
+        assert_eq!(get(&active, "Wounded").and_then(|cond| cond.value), Some(1));
+        assert!(get(&active, "Prone").is_some());
+        assert_eq!(explain_condition(&active, "Off-Guard"), vec![String::from("Off-Guard <- Prone")]);
+    }
+
+    #[test]
+    fn blinded_overrides_dazzled() {
+        let catalog = catalog();
+        let mut active = vec![];
+        gain_condition(&mut active, &catalog, "Dazzled", None).unwrap();
+        gain_condition(&mut active, &catalog, "Blinded", None).unwrap();
+        assert!(get(&active, "Dazzled").is_none());
+        gain_condition(&mut active, &catalog, "Dazzled", None).unwrap();
+        assert!(get(&active, "Dazzled").is_none());
+    }
+
+    #[test]
+    fn grabbed_forces_off_guard_and_immobilized() {
+        let catalog = catalog();
+        let mut active = vec![];
+        gain_condition(&mut active, &catalog, "Grabbed", None).unwrap();
+        for name in ["Off-Guard", "Immobilized"] {
+            let forced = get(&active, name).unwrap();
+            assert_eq!(forced.implied_by, vec![String::from("Grabbed")]);
+            assert!(!forced.explicit);
+        }
+        lose_condition(&mut active, &catalog, "Off-Guard");
+        assert!(get(&active, "Off-Guard").is_some());
+        lose_condition(&mut active, &catalog, "Grabbed");
+        assert!(active.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ancestries::AncestryData, backgrounds::BackgroundData, character::Character, classes::ClassData, feats::Feat, proficiency::ProficiencyLevel, stat_catalog::StatCatalog, stats::{Attributes, ProficiencyType}};
+
+pub const ATTRIBUTE_IDS: [&str; 6] = ["str", "dex", "con", "int", "wis", "cha"];
+
+/// Choices made in the character creation wizard, build turns them into a level 1 character
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CreationChoices {
+    pub name: String,
+    pub ancestry: String,
+    pub heritage: String,
+    pub background: String,
+    pub class: String,
+    pub key_attribute: String,
+    /// One attribute id for every free boost of the ancestry
+    pub ancestry_boosts: Vec<String>,
+    /// The boost chosen from the background options, followed by the free background boost
+    pub background_boosts: Vec<String>,
+    pub free_boosts: Vec<String>,
+    /// Skills trained on top of the ones the background and class give
+    pub skills: Vec<String>,
+    pub feats: Vec<String>,
+}
+
+/// Boosts from a single source have to go to different attributes
+fn check_distinct(boosts: &[String], source: &str) -> Result<(), String> {
+    for (indx, boost) in boosts.iter().enumerate() {
+        if !ATTRIBUTE_IDS.contains(&boost.as_str()) {
+            return Err(format!("{source}: {boost} is not an attribute"));
+        }
+        if boosts[..indx].contains(boost) {
+            return Err(format!("{source}: {boost} can only be boosted once"));
+        }
+    }
+    Ok(())
+}
+
+impl CreationChoices {
+    pub fn get_ancestry<'a>(&self, ancestries: &'a [AncestryData]) -> Result<&'a AncestryData, String> {
+        ancestries.iter().find(|ancestry| ancestry.name == self.ancestry).ok_or(String::from("Choose an ancestry"))
+    }
+
+    pub fn get_background<'a>(&self, backgrounds: &'a [BackgroundData]) -> Result<&'a BackgroundData, String> {
+        backgrounds.iter().find(|background| background.name == self.background).ok_or(String::from("Choose a background"))
+    }
+
+    pub fn get_class<'a>(&self, classes: &'a [ClassData]) -> Result<&'a ClassData, String> {
+        classes.iter().find(|class_data| class_data.name == self.class).ok_or(String::from("Choose a class"))
+    }
+
+    /// Applies every boost and flaw to zeroed attributes, checking the boost rules of each source
+    pub fn get_attributes(&self, ancestries: &[AncestryData], backgrounds: &[BackgroundData], classes: &[ClassData]) -> Result<Attributes, String> {
+        let ancestry = self.get_ancestry(ancestries)?;
+        let background = self.get_background(backgrounds)?;
+        let class_data = self.get_class(classes)?;
+
+        let free_count = ancestry.boosts.iter().filter(|boost| *boost == "free").count();
+        if self.ancestry_boosts.len() != free_count {
+            return Err(format!("{0} has {free_count} free boosts", ancestry.name));
+        }
+        let mut ancestry_boosts: Vec<String> = ancestry.boosts.iter().filter(|boost| *boost != "free").cloned().collect();
+        ancestry_boosts.extend(self.ancestry_boosts.iter().cloned());
+        check_distinct(&ancestry_boosts, &ancestry.name)?;
+
+        if self.background_boosts.len() != 2 {
+            return Err(format!("{0} gives two boosts", background.name));
+        }
+        if !background.boosts.contains(&self.background_boosts[0]) {
+            return Err(format!("The first boost of {0} has to be one of {1:?}", background.name, background.boosts));
+        }
+        check_distinct(&self.background_boosts, &background.name)?;
+
+        if !class_data.key_attributes.contains(&self.key_attribute) {
+            return Err(format!("{0} is not a valid key attribute for {1}, options are {2:?}", self.key_attribute, class_data.name, class_data.key_attributes));
+        }
+
+        if self.free_boosts.len() != 4 {
+            return Err(String::from("Choose four free boosts"));
+        }
+        check_distinct(&self.free_boosts, "Free boosts")?;
+
+        let mut attributes = Attributes::zero();
+        let boosts = ancestry_boosts
+            .iter()
+            .chain(self.background_boosts.iter())
+            .chain(std::iter::once(&self.key_attribute))
+            .chain(self.free_boosts.iter());
+        for boost in boosts {
+            attributes.set_stat(boost, attributes.get_stat_val(boost)? + 1);
+        }
+        for flaw in ancestry.flaws.iter() {
+            attributes.set_stat(flaw, attributes.get_stat_val(flaw)? - 1);
+        }
+        Ok(attributes)
+    }
+
+    /// Skills the player may pick freely. Skills the background and class would both train are replaced by a free pick
+    pub fn get_skill_choice_count(&self, ancestries: &[AncestryData], backgrounds: &[BackgroundData], classes: &[ClassData]) -> Result<i32, String> {
+        let background = self.get_background(backgrounds)?;
+        let class_data = self.get_class(classes)?;
+        let int = self.get_attributes(ancestries, backgrounds, classes)?.get_stat_val("int")?;
+        let overlaps = class_data.trained_skills.iter().filter(|skill| background.trained_skills.contains(skill)).count() as i32;
+        Ok(std::cmp::max(class_data.additional_skills + int + overlaps, 0))
+    }
+
+    pub fn build(&self, ancestries: &[AncestryData], backgrounds: &[BackgroundData], classes: &[ClassData], catalog: &StatCatalog, feats: &HashMap<String, Feat>) -> Result<Character, String> {
+        if self.name.trim().is_empty() {
+            return Err(String::from("The character needs a name"));
+        }
+        let ancestry = self.get_ancestry(ancestries)?;
+        if ancestry.get_heritage(&self.heritage).is_none() {
+            return Err(format!("Choose a heritage of the {0} ancestry", ancestry.name));
+        }
+        let background = self.get_background(backgrounds)?;
+
+        let mut character = Character::zero(catalog);
+        character.name = String::from(self.name.trim());
+        character.ancestry = self.ancestry.clone();
+        character.heritage = self.heritage.clone();
+        character.background = self.background.clone();
+        character.class = self.class.clone();
+        character.key_attribute = self.key_attribute.clone();
+        character.attributes = self.get_attributes(ancestries, backgrounds, classes)?;
+        character.apply_origins(ancestries, backgrounds, classes, catalog)?;
+
+        let skill_count = self.get_skill_choice_count(ancestries, backgrounds, classes)?;
+        if self.skills.len() as i32 > skill_count {
+            return Err(format!("Only {skill_count} additional skills can be trained"));
+        }
+        for skill in self.skills.iter() {
+            match character.get_prof_obj_from_name(skill) {
+                Some(stat) if stat.p_type == ProficiencyType::Skill && stat.proficiency == ProficiencyLevel::Untrained => {},
+                Some(_) => return Err(format!("{skill} is already trained")),
+                None => return Err(format!("{skill} is not a skill")),
+            }
+            character.raise_proficiency(skill, ProficiencyType::Skill, ProficiencyLevel::Trained, catalog)?;
+        }
+
+        if !background.feat.is_empty() {
+            character.feats.push(background.feat.clone());
+        }
+        for feat_name in self.feats.iter().filter(|feat_name| !feat_name.is_empty()) {
+            if !feats.contains_key(feat_name) {
+                return Err(format!("The feat {feat_name} does not exist"));
+            }
+            if !character.feats.contains(feat_name) {
+                character.feats.push(feat_name.clone());
+            }
+        }
+        character.sync_feat_additions(feats, catalog)?;
+        Ok(character)
+    }
+}
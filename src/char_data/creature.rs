@@ -0,0 +1,102 @@
+use super::{conditions::Condition, modifiers::{Modifier, ModifierType, StatBreakdown}, movement::MovementType, proficiency::ProficiencyLevel, rules::{self, ActiveRule}, stats::{Attribute, CalculatedStat}};
+
+/// Penalties can't reduce a speed below this, only conditions that set the speed like Immobilized can
+pub const MIN_SPEED: i32 = 5;
+
+/// The stat math characters and companions share. Both only provide their attributes, level, speeds, rule elements and conditions
+pub trait Creature {
+    fn get_level(&self) -> i32;
+
+    fn get_attribute(&self, id: &str) -> Result<Attribute, String>;
+
+    fn get_base_speed(&self, movement: &MovementType) -> Option<i32>;
+
+    fn get_conditions(&self) -> &Vec<Condition>;
+
+    /// Every rule element of the creature, including the ones of its conditions
+    fn get_active_rules(&self) -> Vec<ActiveRule<'_>>;
+
+    /// Modifiers only this kind of creature has, e.g. the penalties of the armor a character wears
+    fn get_extra_modifiers(&self, _selectors: &[String]) -> Vec<Modifier> {
+        vec![]
+    }
+
+    /// Collects every bonus and penalty the creature has on a stat with the given selectors.
+    /// Stacking rules are not applied yet, see modifiers::apply_stacking
+    fn get_modifiers(&self, selectors: &[String]) -> Vec<Modifier> {
+        let mut modifiers = rules::get_modifiers(&self.get_active_rules(), selectors);
+        modifiers.extend(self.get_extra_modifiers(selectors));
+        modifiers.extend(self.get_conditions().iter().flat_map(|cond| cond.get_modifiers(selectors)));
+        modifiers
+    }
+
+    fn has_roll_option(&self, option: &str) -> bool {
+        rules::get_roll_options(&self.get_active_rules()).contains(&option)
+    }
+
+    /// Source and attribute of the override for a stat with the given selectors, if any
+    fn get_attribute_override(&self, selectors: &[String]) -> Option<(String, String)> {
+        rules::get_ability_override(&self.get_active_rules(), selectors)
+    }
+
+    fn get_notes(&self, selectors: &[String]) -> Vec<String> {
+        rules::get_notes(&self.get_active_rules(), selectors)
+    }
+
+    /// Attribute, proficiency and modifiers of a stat. Extra selectors are for modifiers that only apply to a specific use of the stat, e.g. "initiative"
+    fn calculate_stat_breakdown(&self, stat: &CalculatedStat, extra_selectors: &[String]) -> Result<StatBreakdown, String> {
+        let default_attribute = self.get_attribute(&stat.attribute)?;
+        let (attribute, attribute_source) = match self.get_attribute_override(&stat.get_selectors(default_attribute.get_id())) {
+            Some((_, val)) => {
+                let override_attribute = self.get_attribute(&val)?;
+                let source = format!("{0} (replaces {1})", override_attribute.get_name(), default_attribute.get_name());
+                (override_attribute, source)
+            },
+            None => {
+                let source = String::from(default_attribute.get_name());
+                (default_attribute, source)
+            },
+        };
+        let mut modifiers = vec![
+            Modifier::new(&attribute_source, ModifierType::Ability, attribute.value),
+            Modifier::new(&stat.proficiency.to_string(), ModifierType::Proficiency, stat.proficiency.get_bonus(self.get_level())),
+        ];
+        let mut selectors = stat.get_selectors(attribute.get_id());
+        selectors.extend(extra_selectors.iter().cloned());
+        modifiers.extend(self.get_modifiers(&selectors));
+        Ok(StatBreakdown::new(0, modifiers).with_notes(self.get_notes(&selectors)))
+    }
+
+    /// 10, dexterity up to the armor's cap, the armor proficiency and item bonus and every modifier on AC
+    fn calculate_armor_class(&self, proficiency_name: &str, proficiency: &ProficiencyLevel, dex_cap: Option<i32>, item_bonus: Option<Modifier>) -> Result<StatBreakdown, String> {
+        let dex = self.get_attribute("dex")?.value;
+        let dex_bonus = match dex_cap {
+            Some(dex_cap) => std::cmp::min(dex, dex_cap),
+            None => dex,
+        };
+        let mut modifiers = vec![
+            Modifier::new("Dexterity", ModifierType::Ability, dex_bonus),
+            Modifier::new(proficiency_name, ModifierType::Proficiency, proficiency.get_bonus(self.get_level())),
+        ];
+        modifiers.extend(item_bonus);
+        let selectors = vec![String::from("ac"), String::from("dc")];
+        modifiers.extend(self.get_modifiers(&selectors));
+        Ok(StatBreakdown::new(10, modifiers).with_notes(self.get_notes(&selectors)))
+    }
+
+    /// Speed for a movement type the creature has
+    fn calculate_speed_breakdown(&self, movement: &MovementType) -> Option<StatBreakdown> {
+        let base = self.get_base_speed(movement)?;
+        let selectors = movement.get_selectors();
+        let mut breakdown = StatBreakdown::new(base, self.get_modifiers(&selectors)).with_notes(self.get_notes(&selectors));
+        if breakdown.total < MIN_SPEED {
+            breakdown.set_total("Minimum Speed", MIN_SPEED);
+        }
+        for cond in self.get_conditions().iter() {
+            for value in cond.get_set_values(&selectors) {
+                breakdown.set_total(&cond.name, value);
+            }
+        }
+        Some(breakdown)
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::{leveling::ProficiencyChange, proficiency::ProficiencyLevel, rules::RuleElement, stats::ProficiencyType};
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub enum FeatType{
     General,
@@ -10,25 +12,58 @@ pub enum FeatType{
     Ability
 }
 
+/// An effect a feat has on the character while the character has the feat
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
-pub enum CharAdditionType{
-    ProfIncrease,
-    ProfSet,
+#[serde(tag = "type")]
+pub enum CharAddition{
+    /// Raises the proficiency one step, untrained proficiencies become trained
+    ProfIncrease {name: String, p_type: ProficiencyType},
+    /// Raises the proficiency to at least this level
+    ProfSet {name: String, p_type: ProficiencyType, proficiency: ProficiencyLevel},
+    /// The proficiency uses a different attribute, applied as an AbilityOverride rule element
+    AttributeOverride {name: String, attribute: String},
+    /// Applied as a RollOption rule element
+    Flag {flag: String},
+    HpPerLevel {value: i32},
+    ExtraTactics {value: i32},
+}
 
+/// What applying a CharAddition changed, so it can be reverted exactly
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(tag = "type")]
+pub enum AppliedAddition{
+    Proficiency {change: ProficiencyChange},
+    AttributeOverride {name: String, attribute: String},
+    Flag {flag: String},
+    HpPerLevel {value: i32},
+    ExtraTactics {value: i32},
 }
 
+/// The additions a feat applied to the character
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
-pub struct CharAddition {
+pub struct FeatRecord {
+    pub feat: String,
+    pub applied: Vec<AppliedAddition>,
+    /// Copy of the rule elements of the feat, refreshed whenever the feat additions are synced
+    #[serde(default)]
+    pub rules: Vec<RuleElement>,
+}
 
+fn default_feat_level() -> i32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Feat{
     pub name: String,
     pub actions: i32, 
+    #[serde(default = "default_feat_level")]
+    pub level: i32,
     pub feat_types: Vec<FeatType>,
     pub traits: Vec<String>,
     pub description: String,
     #[serde(default)]
-    pub char_additions: Vec<CharAddition>
+    pub char_additions: Vec<CharAddition>,
+    #[serde(default)]
+    pub rules: Vec<RuleElement>
 }
\ No newline at end of file
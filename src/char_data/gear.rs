@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::{rules::RuleElement, traits};
+
 
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -11,16 +13,162 @@ pub struct Gear {
     pub proficiency: Option<String>,
     pub invested: Option<bool>,
     pub description: String,
-    pub weap_info: Option<WeaponInfo>
+    pub weap_info: Option<WeaponInfo>,
+    pub armor_info: Option<ArmorInfo>,
+    #[serde(default)]
+    pub worn: bool,
+    /// Number of consumables like arrows, None for items that are not counted
+    #[serde(default)]
+    pub quantity: Option<i32>,
+    #[serde(default)]
+    pub rules: Vec<RuleElement>
+}
+
+impl Gear {
+    pub fn has_trait(&self, name: &str) -> bool {
+        traits::has_trait(&self.traits, name)
+    }
+
+    /// Parameter of a trait like the "d8" of "Deadly d8"
+    pub fn trait_value(&self, name: &str) -> Option<String> {
+        traits::trait_value(&self.traits, name)
+    }
+
+    pub fn trait_die(&self, name: &str) -> Option<i32> {
+        traits::trait_die(&self.traits, name)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WeaponInfo {
     pub damage: i32,
     pub w_type: WeaponType,
+    pub d_type: String,
+    /// Weapon group like Flail or Sword, decides the critical specialization effect
+    #[serde(default)]
+    pub group: String,
+    /// Range increment in feet, thrown weapons without one use the distance of their Thrown trait
+    #[serde(default)]
+    pub range: Option<i32>,
+    #[serde(default)]
+    pub reload: Option<i32>,
+    /// Name of the gear used as ammunition, one is used up with every strike
+    #[serde(default)]
+    pub ammunition: Option<String>,
+    #[serde(default)]
+    pub runes: WeaponRunes
+}
+
+/// Damage dice added on top of a strike's damage, e.g. 1d6 fire from a flaming rune
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtraDamage {
+    pub dice: i32,
+    pub die_size: i32,
     pub d_type: String
 }
 
+impl ExtraDamage {
+    pub fn get_text(&self) -> String {
+        format!("{0}d{1} {2}", self.dice, self.die_size, self.d_type)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyRune {
+    pub name: String,
+    #[serde(default)]
+    pub damage: Option<ExtraDamage>,
+    #[serde(default)]
+    pub description: String
+}
+
+/// Potency and striking runes are ignored while automatic bonus progression is used
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeaponRunes {
+    #[serde(default)]
+    pub potency: i32,
+    /// 1 for striking, 2 for greater and 3 for major striking, each adds a damage die
+    #[serde(default)]
+    pub striking: i32,
+    #[serde(default)]
+    pub property: Vec<PropertyRune>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArmorInfo {
+    pub category: ArmorCategory,
+    pub ac_bonus: i32,
+    /// None means the armor does not limit the dexterity bonus
+    pub dex_cap: Option<i32>,
+    #[serde(default)]
+    pub check_penalty: i32,
+    #[serde(default)]
+    pub speed_penalty: i32,
+    /// Strength modifier needed to ignore the check penalty and reduce the speed penalty by 5ft.
+    pub strength: Option<i32>,
+    #[serde(default)]
+    pub runes: ArmorRunes
+}
+
+impl ArmorInfo {
+    pub fn unarmored() -> ArmorInfo {
+        ArmorInfo {
+            category: ArmorCategory::Unarmored,
+            ac_bonus: 0,
+            dex_cap: None,
+            check_penalty: 0,
+            speed_penalty: 0,
+            strength: None,
+            runes: ArmorRunes::default(),
+        }
+    }
+
+    pub fn meets_strength(&self, str_value: i32) -> bool {
+        match self.strength {
+            Some(threshold) => str_value >= threshold,
+            None => true,
+        }
+    }
+
+    pub fn get_check_penalty(&self, str_value: i32) -> i32 {
+        if self.meets_strength(str_value) {0} else {self.check_penalty}
+    }
+
+    pub fn get_speed_penalty(&self, str_value: i32) -> i32 {
+        if self.meets_strength(str_value) {
+            std::cmp::max(self.speed_penalty - 5, 0)
+        }
+        else {
+            self.speed_penalty
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArmorRunes {
+    #[serde(default)]
+    pub potency: i32,
+    #[serde(default)]
+    pub resilient: i32,
+    #[serde(default)]
+    pub property: Vec<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArmorCategory {
+    Unarmored,
+    Light,
+    Medium,
+    Heavy
+}
+
+impl ArmorCategory {
+    /// Name of the armor proficiency that is used for this category
+    pub fn get_proficiency_name(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WeaponType {
     Melee,
@@ -3,11 +3,16 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct HpInfo {
+    #[serde(default)]
     ancestry_hp: i32,
+    #[serde(default)]
     class_hp:i32,
     max_hp: i32,
     current_hp: i32,
-    temp_hp:i32
+    temp_hp:i32,
+    /// Extra hp per level from feats like Toughness
+    #[serde(default)]
+    bonus_per_level: i32
 }
 
 impl HpInfo {
@@ -18,17 +23,34 @@ impl HpInfo {
             max_hp: ancestry_hp + (class_hp + con) * level,
             current_hp: ancestry_hp + (class_hp + con) * level,
             temp_hp: 0,
+            bonus_per_level: 0,
         }
     }
 
     pub fn calculate_max_hp(self: &mut Self, level: i32, con: i32) -> i32{
-        let new_max_hp = self.ancestry_hp + (self.class_hp + con) * level;
+        let new_max_hp = self.ancestry_hp + (self.class_hp + con + self.bonus_per_level) * level;
         let diff = new_max_hp - self.max_hp;
-        self.max_hp = self.ancestry_hp + (self.class_hp + con) * level;
+        self.max_hp = new_max_hp;
         self.change_hp(diff);
         self.max_hp
     }
 
+    /// Replaces the extra hp per level, call calculate_max_hp afterwards
+    pub fn set_bonus_per_level(self: &mut Self, bonus_per_level: i32) {
+        self.bonus_per_level = bonus_per_level;
+    }
+
+    /// Replaces the per level hp sources, call calculate_max_hp afterwards
+    pub fn set_sources(self: &mut Self, ancestry_hp: i32, class_hp: i32) {
+        self.ancestry_hp = ancestry_hp;
+        self.class_hp = class_hp;
+    }
+
+    /// Hit points per level of the ancestry and the class
+    pub fn get_sources(self: &Self) -> (i32, i32) {
+        (self.ancestry_hp, self.class_hp)
+    }
+
     pub fn get_max_hp(self: &Self) -> i32{
         return self.max_hp;
     }
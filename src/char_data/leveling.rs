@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use super::{proficiency::ProficiencyLevel, stats::ProficiencyType};
+
+pub const MAX_LEVEL: i32 = 20;
+pub const BOOST_LEVELS: [i32; 4] = [5, 10, 15, 20];
+pub const BOOSTS_PER_BOOST_LEVEL: usize = 4;
+/// Boosting an attribute at this modifier or above only gives a partial boost
+pub const PARTIAL_BOOST_THRESHOLD: i32 = 4;
+
+pub fn has_attribute_boosts(level: i32) -> bool {
+    BOOST_LEVELS.contains(&level)
+}
+
+/// A proficiency a level changed, previous is None if the level added the proficiency
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProficiencyChange {
+    pub name: String,
+    pub p_type: ProficiencyType,
+    pub previous: Option<ProficiencyLevel>,
+    pub new: ProficiencyLevel,
+}
+
+/// What the player picks when leveling up. Feats line up with the feat slots of the level, empty names leave a slot open
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LevelUpChoices {
+    pub boosts: Vec<String>,
+    pub skill_increase: String,
+    pub feats: Vec<String>,
+}
+
+/// Everything a single level gave the character, so leveling down can undo exactly that
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LevelRecord {
+    pub level: i32,
+    #[serde(default)]
+    pub boosts: Vec<String>,
+    /// Attribute values and pending partial boosts from before the boosts of this level
+    #[serde(default)]
+    pub previous_attributes: Vec<i32>,
+    #[serde(default)]
+    pub previous_partial_boosts: Vec<String>,
+    #[serde(default)]
+    pub proficiency_changes: Vec<ProficiencyChange>,
+    #[serde(default)]
+    pub feats: Vec<String>,
+    #[serde(default)]
+    pub class_features: Vec<String>,
+}
@@ -1,5 +1,6 @@
 pub mod proficiency;
 pub mod stats;
+pub mod stat_catalog;
 pub mod traits;
 pub mod tactics;
 pub mod gear;
@@ -7,4 +8,17 @@ pub mod hp;
 pub mod feats;
 pub mod auto_bonus_prog;
 pub mod conditions;
+pub mod modifiers;
+pub mod rules;
+pub mod movement;
+pub mod rolls;
+pub mod spells;
+pub mod classes;
+pub mod creature;
+pub mod companion;
+pub mod strikes;
+pub mod leveling;
+pub mod ancestries;
+pub mod backgrounds;
 pub mod character;
+pub mod creation;
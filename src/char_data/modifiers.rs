@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ModifierType {
+    Ability,
+    Proficiency,
+    Status,
+    Circumstance,
+    Item,
+    #[default]
+    Untyped
+}
+
+impl ModifierType {
+    /// Ability, proficiency and untyped modifiers always add up, all other types only use their best bonus and worst penalty
+    pub fn always_stacks(&self) -> bool {
+        matches!(self, ModifierType::Ability | ModifierType::Proficiency | ModifierType::Untyped)
+    }
+}
+
+impl fmt::Display for ModifierType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<&str> for ModifierType {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "ability" => Self::Ability,
+            "proficiency" => Self::Proficiency,
+            "status" => Self::Status,
+            "circumstance" => Self::Circumstance,
+            "item" => Self::Item,
+            _ => Self::Untyped,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Modifier {
+    pub source: String,
+    pub m_type: ModifierType,
+    pub value: i32,
+}
+
+impl Modifier {
+    pub fn new(source: &str, m_type: ModifierType, value: i32) -> Modifier {
+        Modifier {
+            source: String::from(source),
+            m_type,
+            value,
+        }
+    }
+}
+
+/// Filters the modifiers down to the ones that count according to the stacking rules.
+/// Of each typed modifier only the highest bonus and the worst penalty remain, the first one wins on a tie.
+pub fn apply_stacking(modifiers: &[Modifier]) -> Vec<Modifier> {
+    modifiers
+        .iter()
+        .zip(get_stacking_mask(modifiers))
+        .filter(|(_, counts)| *counts)
+        .map(|(modifier, _)| modifier.clone())
+        .collect()
+}
+
+/// For every modifier, whether it counts according to the stacking rules
+fn get_stacking_mask(modifiers: &[Modifier]) -> Vec<bool> {
+    let mut best_bonus: HashMap<ModifierType, usize> = HashMap::new();
+    let mut worst_penalty: HashMap<ModifierType, usize> = HashMap::new();
+    for (indx, modifier) in modifiers.iter().enumerate() {
+        if modifier.m_type.always_stacks() || modifier.value == 0 {
+            continue;
+        }
+        // Bonuses and penalties are kept apart, so within one map the better one is the one with the bigger magnitude
+        let map = if modifier.value > 0 {&mut best_bonus} else {&mut worst_penalty};
+        match map.get(&modifier.m_type) {
+            Some(old_indx) if modifier.value.abs() <= modifiers[*old_indx].value.abs() => {},
+            _ => {map.insert(modifier.m_type.clone(), indx);},
+        }
+    }
+    modifiers
+        .iter()
+        .enumerate()
+        .map(|(indx, modifier)| {
+            modifier.m_type.always_stacks()
+            || best_bonus.get(&modifier.m_type) == Some(&indx)
+            || worst_penalty.get(&modifier.m_type) == Some(&indx)
+        })
+        .collect()
+}
+
+pub fn total_modifiers(modifiers: &[Modifier]) -> i32 {
+    apply_stacking(modifiers).iter().map(|modifier| modifier.value).sum()
+}
+
+/// Itemized result of a stat calculation, every modifier that was considered and whether it counted
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatBreakdown {
+    pub base: i32,
+    pub applied: Vec<Modifier>,
+    pub ignored: Vec<Modifier>,
+    pub total: i32,
+    /// Situational notes from rule elements, shown below the modifiers
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+impl StatBreakdown {
+    pub fn new(base: i32, modifiers: Vec<Modifier>) -> StatBreakdown {
+        let mask = get_stacking_mask(&modifiers);
+        let (applied, ignored): (Vec<_>, Vec<_>) = modifiers
+            .into_iter()
+            .zip(mask)
+            .partition(|(_, counts)| *counts);
+        let applied: Vec<Modifier> = applied.into_iter().map(|(modifier, _)| modifier).collect();
+        let ignored: Vec<Modifier> = ignored.into_iter().map(|(modifier, _)| modifier).collect();
+        let total = base + applied.iter().map(|modifier| modifier.value).sum::<i32>();
+        StatBreakdown {
+            base,
+            applied,
+            ignored,
+            total,
+            notes: vec![],
+        }
+    }
+
+    pub fn with_notes(mut self, notes: Vec<String>) -> StatBreakdown {
+        self.notes.extend(notes);
+        self
+    }
+
+    /// Same modifiers on top of a different base, e.g. 10 for a DC
+    pub fn with_base(mut self, base: i32) -> StatBreakdown {
+        self.total += base - self.base;
+        self.base = base;
+        self
+    }
+
+    /// Forces the total to a value, keeping a line in the breakdown that explains the difference
+    pub fn set_total(&mut self, source: &str, value: i32) {
+        self.applied.push(Modifier::new(source, ModifierType::Untyped, value - self.total));
+        self.total = value;
+    }
+
+    pub fn get_lines(&self) -> Vec<String> {
+        let format_modifier = |modifier: &Modifier| {
+            let prefix = if modifier.value >= 0 {"+"} else {""};
+            format!("{0} ({1}) {prefix}{2}", modifier.source, modifier.m_type, modifier.value)
+        };
+        let mut lines = vec![];
+        if self.base != 0 {
+            lines.push(format!("Base {0}", self.base));
+        }
+        lines.extend(self.applied.iter().map(format_modifier));
+        lines.extend(self.ignored.iter().map(|modifier| format!("{0} [does not stack]", format_modifier(modifier))));
+        lines.extend(self.notes.iter().cloned());
+        lines
+    }
+
+    pub fn get_tooltip(&self) -> String {
+        self.get_lines().join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn largest_typed_bonus_and_worst_typed_penalty_win() {
+        let modifiers = vec![
+            Modifier::new("Inspire Courage", ModifierType::Status, 1),
+            Modifier::new("Heroism", ModifierType::Status, 2),
+            Modifier::new("Frightened 1", ModifierType::Status, -1),
+            Modifier::new("Sickened 2", ModifierType::Status, -2),
+            Modifier::new("Aid", ModifierType::Circumstance, 1),
+            Modifier::new("Multiple Attack", ModifierType::Untyped, -5),
+            Modifier::new("Range increment 2", ModifierType::Untyped, -2),
+        ];
+        let breakdown = StatBreakdown::new(10, modifiers.clone());
+        let applied: Vec<&str> = breakdown.applied.iter().map(|modifier| modifier.source.as_str()).collect();
+        let ignored: Vec<&str> = breakdown.ignored.iter().map(|modifier| modifier.source.as_str()).collect();
+        assert_eq!(applied, vec!["Heroism", "Sickened 2", "Aid", "Multiple Attack", "Range increment 2"]);
+        assert_eq!(ignored, vec!["Inspire Courage", "Frightened 1"]);
+        assert_eq!(breakdown.total, 4);
+        assert_eq!(total_modifiers(&modifiers), -6);
+    }
+
+    #[test]
+    fn first_modifier_wins_a_tie() {
+        let modifiers = vec![
+            Modifier::new("Potency Rune", ModifierType::Item, 1),
+            Modifier::new("Bracers", ModifierType::Item, 1),
+        ];
+        assert_eq!(apply_stacking(&modifiers), vec![modifiers[0].clone()]);
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Size {
+    Tiny,
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Huge,
+    Gargantuan
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementType {
+    Land,
+    Swim,
+    Climb,
+    Fly,
+    Burrow
+}
+
+impl MovementType {
+    pub fn all() -> Vec<MovementType> {
+        vec![MovementType::Land, MovementType::Swim, MovementType::Climb, MovementType::Fly, MovementType::Burrow]
+    }
+
+    /// Selectors for modifiers on this speed, "speed" applies to every movement type
+    pub fn get_selectors(&self) -> Vec<String> {
+        vec![String::from("speed"), format!("{0}-speed", self.to_string().to_lowercase())]
+    }
+}
+
+impl fmt::Display for MovementType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Base speeds in feet, a missing speed means the creature can't move that way on its own
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Speeds {
+    pub land: i32,
+    pub swim: Option<i32>,
+    pub climb: Option<i32>,
+    pub fly: Option<i32>,
+    pub burrow: Option<i32>,
+}
+
+impl Default for Speeds {
+    fn default() -> Self {
+        Speeds {
+            land: 25,
+            swim: None,
+            climb: None,
+            fly: None,
+            burrow: None,
+        }
+    }
+}
+
+impl Speeds {
+    pub fn get_base(&self, movement: &MovementType) -> Option<i32> {
+        match movement {
+            MovementType::Land => Some(self.land),
+            MovementType::Swim => self.swim,
+            MovementType::Climb => self.climb,
+            MovementType::Fly => self.fly,
+            MovementType::Burrow => self.burrow,
+        }
+    }
+}
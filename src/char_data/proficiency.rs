@@ -2,7 +2,7 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone,Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone,Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ProficiencyLevel{
     Untrained,
     Half,
@@ -13,6 +13,26 @@ pub enum ProficiencyLevel{
 }
 
 impl ProficiencyLevel {
+    /// The next step of a skill increase, None once legendary
+    pub fn next(&self) -> Option<ProficiencyLevel> {
+        match *self {
+            ProficiencyLevel::Untrained | ProficiencyLevel::Half => Some(ProficiencyLevel::Trained),
+            ProficiencyLevel::Trained => Some(ProficiencyLevel::Expert),
+            ProficiencyLevel::Expert => Some(ProficiencyLevel::Master),
+            ProficiencyLevel::Master => Some(ProficiencyLevel::Legendary),
+            ProficiencyLevel::Legendary => None,
+        }
+    }
+
+    /// Lowest character level a skill increase can reach this proficiency at
+    pub fn get_min_level(&self) -> i32 {
+        match *self {
+            ProficiencyLevel::Master => 7,
+            ProficiencyLevel::Legendary => 15,
+            _ => 1,
+        }
+    }
+
     pub fn get_bonus(&self, level: i32) -> i32{
         match *self {
             ProficiencyLevel::Untrained => 0,
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use super::modifiers::StatBreakdown;
+
+/// A d20 roll of a stat, keeps the breakdown the roll was made with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RollResult {
+    pub name: String,
+    pub die: i32,
+    pub breakdown: StatBreakdown,
+    pub total: i32,
+}
+
+impl RollResult {
+    pub fn new(name: &str, die: i32, breakdown: StatBreakdown) -> RollResult {
+        RollResult {
+            name: String::from(name),
+            die,
+            total: die + breakdown.total,
+            breakdown,
+        }
+    }
+
+    pub fn get_text(&self) -> String {
+        let prefix = if self.breakdown.total >= 0 {"+"} else {""};
+        format!("{0}: {1} ({2} {prefix}{3})", self.name, self.total, self.die, self.breakdown.total)
+    }
+}
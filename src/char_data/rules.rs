@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::{gear::Gear, modifiers::{Modifier, ModifierType}};
+
+/// An effect described in json that feats, gear, tactics and conditions can carry.
+/// Selectors are the same lowercase names stats use, see CalculatedStat::get_selectors
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "key")]
+pub enum RuleElement {
+    /// A bonus or penalty on every stat with the selector, only while all roll options in the predicate are set
+    FlatModifier {
+        selector: String,
+        #[serde(default)]
+        m_type: ModifierType,
+        value: i32,
+        #[serde(default)]
+        label: Option<String>,
+        #[serde(default)]
+        predicate: Vec<String>,
+    },
+    /// Sets an option that predicates and the rest of the sheet can check, e.g. "fleet"
+    RollOption {option: String},
+    /// Stats with the selector use a different attribute
+    AbilityOverride {selector: String, attribute: String},
+    GrantItem {item: Box<Gear>},
+    GrantCondition {
+        condition: String,
+        #[serde(default)]
+        value: Option<i32>,
+    },
+    /// A reminder shown next to stats with the selector
+    Note {selector: String, text: String},
+    /// A focus spell from resources/spells.json, non-cantrip focus spells add a point to the focus pool
+    FocusSpell {spell: String},
+}
+
+impl RuleElement {
+    /// The selector of rules that target stats
+    pub fn get_selector(&self) -> Option<&String> {
+        match self {
+            RuleElement::FlatModifier {selector, ..} | RuleElement::AbilityOverride {selector, ..} | RuleElement::Note {selector, ..} => Some(selector),
+            _ => None,
+        }
+    }
+
+    pub fn get_selector_mut(&mut self) -> Option<&mut String> {
+        match self {
+            RuleElement::FlatModifier {selector, ..} | RuleElement::AbilityOverride {selector, ..} | RuleElement::Note {selector, ..} => Some(selector),
+            _ => None,
+        }
+    }
+}
+
+/// A rule element of something the character currently has, with the name of that source.
+/// Borrows the rule from its source, only rules the sheet derives itself are owned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveRule<'a> {
+    pub source: &'a str,
+    pub rule: Cow<'a, RuleElement>,
+}
+
+impl<'a> ActiveRule<'a> {
+    pub fn new(source: &'a str, rule: &'a RuleElement) -> ActiveRule<'a> {
+        ActiveRule {
+            source,
+            rule: Cow::Borrowed(rule),
+        }
+    }
+
+    pub fn derived(source: &'a str, rule: RuleElement) -> ActiveRule<'a> {
+        ActiveRule {
+            source,
+            rule: Cow::Owned(rule),
+        }
+    }
+}
+
+pub fn get_roll_options<'a>(rules: &'a [ActiveRule]) -> Vec<&'a str> {
+    rules
+        .iter()
+        .filter_map(|active| match active.rule.as_ref() {
+            RuleElement::RollOption {option} => Some(option.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn get_modifiers(rules: &[ActiveRule], selectors: &[String]) -> Vec<Modifier> {
+    let roll_options = get_roll_options(rules);
+    rules
+        .iter()
+        .filter_map(|active| match active.rule.as_ref() {
+            RuleElement::FlatModifier {selector, m_type, value, label, predicate}
+                if selectors.contains(selector) && predicate.iter().all(|option| roll_options.contains(&option.as_str())) => {
+                let source = label.as_deref().unwrap_or(active.source);
+                Some(Modifier::new(source, m_type.clone(), *value))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The attribute the last matching override sets, together with its source
+pub fn get_ability_override(rules: &[ActiveRule], selectors: &[String]) -> Option<(String, String)> {
+    rules
+        .iter()
+        .filter_map(|active| match active.rule.as_ref() {
+            RuleElement::AbilityOverride {selector, attribute} if selectors.contains(selector) => Some((String::from(active.source), attribute.clone())),
+            _ => None,
+        })
+        .next_back()
+}
+
+pub fn get_notes(rules: &[ActiveRule], selectors: &[String]) -> Vec<String> {
+    rules
+        .iter()
+        .filter_map(|active| match active.rule.as_ref() {
+            RuleElement::Note {selector, text} if selectors.contains(selector) => Some(format!("{0}: {text}", active.source)),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn get_granted_items(rules: &[ActiveRule]) -> Vec<Gear> {
+    rules
+        .iter()
+        .filter_map(|active| match active.rule.as_ref() {
+            RuleElement::GrantItem {item} => Some(item.as_ref().clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Names of the granted focus spells, every spell only once
+pub fn get_focus_spells(rules: &[ActiveRule]) -> Vec<String> {
+    let mut spells: Vec<String> = vec![];
+    for active in rules.iter() {
+        if let RuleElement::FocusSpell {spell} = active.rule.as_ref() {
+            if !spells.contains(spell) {
+                spells.push(spell.clone());
+            }
+        }
+    }
+    spells
+}
+
+/// Source, condition name and value of every granted condition
+pub fn get_granted_conditions(rules: &[ActiveRule]) -> Vec<(String, String, Option<i32>)> {
+    rules
+        .iter()
+        .filter_map(|active| match active.rule.as_ref() {
+            RuleElement::GrantCondition {condition, value} => Some((String::from(active.source), condition.clone(), *value)),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+
+use super::traits;
+
+pub const MAX_SPELL_RANK: i32 = 10;
+pub const MAX_FOCUS_POINTS: i32 = 3;
+
+/// Extra effect of a spell cast at a higher rank. Fixed entries apply from their rank on,
+/// interval entries apply once for every `interval` ranks above the spell's rank
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HeightenEntry {
+    #[serde(default)]
+    pub rank: i32,
+    #[serde(default)]
+    pub interval: i32,
+    pub text: String,
+}
+
+/// A spell as listed in resources/spells.json, cantrips have rank 0
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Spell {
+    pub name: String,
+    pub rank: i32,
+    #[serde(default)]
+    pub actions: i32,
+    #[serde(default)]
+    pub traditions: Vec<String>,
+    #[serde(default)]
+    pub traits: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub heightened: Vec<HeightenEntry>,
+}
+
+impl Spell {
+    pub fn is_cantrip(&self) -> bool {
+        self.rank == 0 || traits::has_trait(&self.traits, "Cantrip")
+    }
+
+    /// Lowest rank the spell can be cast at, cantrips count as rank 1
+    pub fn get_min_rank(&self) -> i32 {
+        std::cmp::max(self.rank, 1)
+    }
+
+    /// Heightened effects that apply when cast at the given rank
+    pub fn get_heightened_text(&self, rank: i32) -> Vec<String> {
+        let above = rank - self.get_min_rank();
+        self.heightened
+            .iter()
+            .filter_map(|entry| {
+                if entry.interval > 0 {
+                    let times = above / entry.interval;
+                    (times > 0).then(|| format!("(+{0}) x{times}: {1}", entry.interval, entry.text))
+                }
+                else {
+                    (entry.rank <= rank).then(|| format!("({0}) {1}", entry.rank, entry.text))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Highest spell rank a full caster can cast at this level, also the rank cantrips are heightened to
+pub fn get_max_spell_rank(level: i32) -> i32 {
+    std::cmp::min((level + 1) / 2, MAX_SPELL_RANK)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CastingType {
+    #[default]
+    Prepared,
+    Spontaneous,
+}
+
+/// Spellcasting a class gives, loaded as part of resources/classes.json
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClassSpellcasting {
+    /// Name of the Spell proficiency used for spell attacks and DCs, e.g. "Arcane"
+    pub tradition: String,
+    pub casting_type: CastingType,
+    pub slots_per_rank: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpellSlots {
+    pub rank: i32,
+    pub max: i32,
+    #[serde(default)]
+    pub used: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PreparedSpell {
+    pub rank: i32,
+    pub spell: String,
+    #[serde(default)]
+    pub expended: bool,
+}
+
+/// Spells the character knows and the slots to cast them with.
+/// Known spells are the repertoire of a spontaneous caster or the spellbook of a prepared one
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Spellcasting {
+    pub tradition: String,
+    pub casting_type: CastingType,
+    /// Slots of every rank below the highest one, 0 keeps manually set slots
+    #[serde(default)]
+    pub slots_per_rank: i32,
+    #[serde(default)]
+    pub known: Vec<String>,
+    #[serde(default)]
+    pub prepared: Vec<PreparedSpell>,
+    #[serde(default)]
+    pub slots: Vec<SpellSlots>,
+}
+
+impl Spellcasting {
+    pub fn new(class_spellcasting: &ClassSpellcasting) -> Spellcasting {
+        Spellcasting {
+            tradition: class_spellcasting.tradition.clone(),
+            casting_type: class_spellcasting.casting_type.clone(),
+            slots_per_rank: class_spellcasting.slots_per_rank,
+            known: vec![],
+            prepared: vec![],
+            slots: vec![],
+        }
+    }
+
+    /// Slot counts of a full caster: the highest rank only has one slot less on the level it is gained, rank 10 only ever has one
+    pub fn get_slot_counts(&self, level: i32) -> Vec<SpellSlots> {
+        let max_rank = get_max_spell_rank(level);
+        (1..=max_rank)
+            .map(|rank| {
+                let max = if rank == MAX_SPELL_RANK {
+                    1
+                }
+                else if rank == max_rank && level % 2 == 1 {
+                    self.slots_per_rank - 1
+                }
+                else {
+                    self.slots_per_rank
+                };
+                SpellSlots {rank, max, used: 0}
+            })
+            .collect()
+    }
+
+    /// Recalculates the slot counts for a level, keeping used slots where the rank still exists.
+    /// Prepared spells of ranks that no longer have slots are dropped
+    pub fn update_slots(&mut self, level: i32) {
+        if self.slots_per_rank <= 0 {
+            return;
+        }
+        let mut slots = self.get_slot_counts(level);
+        for slot in slots.iter_mut() {
+            if let Some(old) = self.slots.iter().find(|old| old.rank == slot.rank) {
+                slot.used = std::cmp::min(old.used, slot.max);
+            }
+        }
+        self.slots = slots;
+        let max_rank = get_max_spell_rank(level);
+        self.prepared.retain(|prepared| prepared.rank <= max_rank);
+    }
+
+    fn get_slots_mut(&mut self, rank: i32) -> Result<&mut SpellSlots, String> {
+        self.slots.iter_mut().find(|slot| slot.rank == rank).ok_or(format!("There are no rank {rank} spell slots"))
+    }
+
+    pub fn expend_slot(&mut self, rank: i32) -> Result<(), String> {
+        let slot = self.get_slots_mut(rank)?;
+        if slot.used >= slot.max {
+            return Err(format!("All rank {rank} spell slots are used"));
+        }
+        slot.used += 1;
+        Ok(())
+    }
+
+    pub fn restore_slot(&mut self, rank: i32) -> Result<(), String> {
+        let slot = self.get_slots_mut(rank)?;
+        slot.used = std::cmp::max(slot.used - 1, 0);
+        Ok(())
+    }
+
+    pub fn learn_spell(&mut self, spell: &Spell) -> Result<(), String> {
+        if !spell.traditions.is_empty() && !spell.traditions.contains(&self.tradition) {
+            return Err(format!("{0} is not a spell of the {1} tradition", spell.name, self.tradition));
+        }
+        if !self.known.contains(&spell.name) {
+            self.known.push(spell.name.clone());
+        }
+        Ok(())
+    }
+
+    pub fn forget_spell(&mut self, spell_name: &str) {
+        self.known.retain(|known| known != spell_name);
+        self.prepared.retain(|prepared| prepared.spell != spell_name);
+    }
+
+    /// Prepares a known spell into a slot of the given rank, cantrips don't need a slot
+    pub fn prepare_spell(&mut self, spell: &Spell, rank: i32) -> Result<(), String> {
+        if self.casting_type != CastingType::Prepared {
+            return Err(String::from("Only prepared casters prepare spells"));
+        }
+        if !self.known.contains(&spell.name) {
+            return Err(format!("{0} is not in the spellbook", spell.name));
+        }
+        if spell.is_cantrip() {
+            return Err(format!("{0} is a cantrip and can always be cast", spell.name));
+        }
+        if rank < spell.rank {
+            return Err(format!("{0} can't be prepared below rank {1}", spell.name, spell.rank));
+        }
+        let max = self.slots.iter().find(|slot| slot.rank == rank).map(|slot| slot.max).unwrap_or(0);
+        if self.prepared.iter().filter(|prepared| prepared.rank == rank).count() as i32 >= max {
+            return Err(format!("All rank {rank} slots already have a spell prepared"));
+        }
+        self.prepared.push(PreparedSpell {rank, spell: spell.name.clone(), expended: false});
+        Ok(())
+    }
+
+    pub fn unprepare_spell(&mut self, indx: usize) {
+        if indx < self.prepared.len() {
+            self.prepared.remove(indx);
+        }
+    }
+
+    /// Casts a spell at a rank, expending the prepared spell or a spontaneous slot. Cantrips are free
+    pub fn cast_spell(&mut self, spell: &Spell, rank: i32) -> Result<(), String> {
+        if !self.known.contains(&spell.name) {
+            return Err(format!("{0} is not a known spell", spell.name));
+        }
+        if spell.is_cantrip() {
+            return Ok(());
+        }
+        match self.casting_type {
+            CastingType::Prepared => {
+                let prepared = self.prepared
+                    .iter_mut()
+                    .find(|prepared| prepared.spell == spell.name && prepared.rank == rank && !prepared.expended)
+                    .ok_or(format!("{0} is not prepared at rank {rank}", spell.name))?;
+                prepared.expended = true;
+                Ok(())
+            },
+            CastingType::Spontaneous => {
+                if rank < spell.rank {
+                    return Err(format!("{0} can't be cast below rank {1}", spell.name, spell.rank));
+                }
+                self.expend_slot(rank)
+            },
+        }
+    }
+
+    /// Daily preparations: every slot and prepared spell is available again
+    pub fn rest(&mut self) {
+        for slot in self.slots.iter_mut() {
+            slot.used = 0;
+        }
+        for prepared in self.prepared.iter_mut() {
+            prepared.expended = false;
+        }
+    }
+}
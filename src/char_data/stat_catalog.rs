@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use super::{proficiency::ProficiencyLevel, stats::{CalculatedStat, ProficiencyType}};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatDefinition {
+    pub name: String,
+    pub p_type: ProficiencyType,
+    pub attribute: String,
+    /// Proficiency a new character starts with
+    pub proficiency: ProficiencyLevel,
+}
+
+/// Skills, saves and other proficiencies with their default attributes, loaded from resources/skills.json
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatCatalog {
+    pub stats: Vec<StatDefinition>,
+    /// Attribute used for proficiencies of a type that are not listed by name, e.g. every Lore
+    #[serde(default)]
+    pub type_attributes: HashMap<ProficiencyType, String>,
+}
+
+impl StatCatalog {
+    pub fn get_definition(&self, name: &str, p_type: &ProficiencyType) -> Option<&StatDefinition> {
+        self.stats.iter().find(|stat| stat.name == name && &stat.p_type == p_type)
+    }
+
+    pub fn get_attribute(&self, name: &str, p_type: &ProficiencyType) -> Result<String, String> {
+        if let Some(definition) = self.get_definition(name, p_type) {
+            return Ok(definition.attribute.clone());
+        }
+        match self.type_attributes.get(p_type) {
+            Some(attribute) => Ok(attribute.clone()),
+            None => Err(format!("The {p_type:?} {name} does not exist in the skill catalog")),
+        }
+    }
+
+    pub fn create_stat(&self, name: &str, p_type: ProficiencyType, proficiency: ProficiencyLevel) -> Result<CalculatedStat, String> {
+        let attribute = self.get_attribute(name, &p_type)?;
+        Ok(CalculatedStat::new(p_type, &attribute, name, proficiency))
+    }
+
+    /// Every stat in the catalog at its starting proficiency
+    pub fn default_array(&self) -> Vec<CalculatedStat> {
+        self.stats
+            .iter()
+            .map(|stat| CalculatedStat::new(stat.p_type.clone(), &stat.attribute, &stat.name, stat.proficiency.clone()))
+            .collect()
+    }
+}
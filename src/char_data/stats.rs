@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use leptos::logging::log;
-use super::{character::Character, proficiency::ProficiencyLevel};
+use super::{character::Character, creature::Creature, modifiers::StatBreakdown, proficiency::ProficiencyLevel};
 
 
 #[derive(Debug, Clone, Deserialize, Serialize, Eq)]
@@ -116,10 +116,8 @@ impl Attributes {
         }
     }
 
+    /// Looks up a real attribute id, the "key" pseudo id has to be resolved through Character::get_attribute
     pub fn get_stat(&self, id: &str) -> Result<Attribute, String> {
-        if id == "key" {
-            return Ok(self.intelligence.clone())
-        }
         for s in self.as_vec() {
             if s.get_id() == id {
                 return Ok(s.clone());
@@ -129,9 +127,6 @@ impl Attributes {
     }
 
     pub fn get_stat_val(&self, id: &str) -> Result<i32, String> {
-        if id == "key" {
-            return Ok(self.intelligence.value)
-        }
         for s in self.as_vec() {
             if s.get_id() == id {
                 return Ok(s.value);
@@ -177,7 +172,7 @@ impl From<&Vec<i32>> for Attributes {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ProficiencyType {
     Save,
     Skill,
@@ -225,56 +220,38 @@ impl CalculatedStat {
         }
     }
 
-    pub fn default_array() -> Vec<CalculatedStat> {
-        vec![
-                CalculatedStat::default_skill("dex", "Acrobatics"),
-                CalculatedStat::default_skill("int", "Arcana"),
-                CalculatedStat::default_skill("str", "Athletics"),
-                CalculatedStat::default_skill("int", "Crafting"),
-                CalculatedStat::default_skill("cha", "Deception"),
-                CalculatedStat::default_skill("cha", "Diplomacy"),
-                CalculatedStat::default_skill("cha", "Intimidation"),
-                CalculatedStat::default_skill("wis", "Medicine"),
-                CalculatedStat::default_skill("wis", "Nature"),
-                CalculatedStat::default_skill("int", "Occultism"),
-                CalculatedStat::default_skill("cha", "Performance"),
-                CalculatedStat::default_skill("wis", "Religion"),
-                CalculatedStat::default_skill("int", "Society"),
-                CalculatedStat::default_skill("dex", "Stealth"),
-                CalculatedStat::default_skill("wis", "Survival"),
-                CalculatedStat::default_skill("dex", "Thievery"),
-                CalculatedStat::default_save("con", "Fortitude"),
-                CalculatedStat::default_save("dex", "Reflex"),
-                CalculatedStat::default_save("wis", "Will"),
-                CalculatedStat::new(ProficiencyType::Armor, "dex", "Unarmored", ProficiencyLevel::Trained),
-                CalculatedStat::new(ProficiencyType::Armor, "dex", "Light", ProficiencyLevel::Untrained),
-                CalculatedStat::new(ProficiencyType::Armor, "dex", "Medium", ProficiencyLevel::Untrained),
-                CalculatedStat::new(ProficiencyType::Armor, "dex", "Heavy", ProficiencyLevel::Untrained),
-                CalculatedStat::new(ProficiencyType::Weapon, "str", "Simple", ProficiencyLevel::Untrained),
-                CalculatedStat::new(ProficiencyType::Weapon, "str", "Martial", ProficiencyLevel::Untrained),
-                CalculatedStat::new(ProficiencyType::Weapon, "str", "Advanced", ProficiencyLevel::Untrained),
-                CalculatedStat::new(ProficiencyType::Perception, "wis", "Perception", ProficiencyLevel::Untrained),
-                CalculatedStat::new(ProficiencyType::ClassDC, "key", "ClassDC", ProficiencyLevel::Trained),
-            ]
+    /// Selectors used to match condition penalties against this stat, e.g. "athletics", "str", "skill", "check"
+    pub fn get_selectors(self: &Self, attribute_id: &str) -> Vec<String> {
+        let mut selectors = vec![self.name.to_lowercase(), String::from(attribute_id)];
+        let type_selectors: Vec<&str> = match self.p_type {
+            ProficiencyType::Save => vec!["save", "check"],
+            ProficiencyType::Skill | ProficiencyType::Lore => vec!["skill", "check"],
+            ProficiencyType::Perception => vec!["perception", "check"],
+            ProficiencyType::Weapon => vec!["attack", "check"],
+            // Spell attacks add "check" themselves, spell DCs are no checks
+            ProficiencyType::Spell => vec!["spell"],
+            ProficiencyType::ClassDC => vec!["dc"],
+            ProficiencyType::Armor => vec!["ac", "dc"],
+        };
+        selectors.extend(type_selectors.into_iter().map(String::from));
+        selectors
     }
 
     pub fn calculate_stat(self: &Self, character: &Character) -> i32 {
-        let attribute_name = self.attribute.clone();
-        let char_attributes = &character.attributes;
-        let mut base_val: Result<Attribute, String> = char_attributes.get_stat(&attribute_name);
-        let skill_auto_bonus_prog = match character.abp_data.skill_pot.get(&self.name) {
-            Some(value) => *value,
-            None => 0,
-        };
-        match character.override_prof.get(&self.name) {
-            Some(val) => {base_val = char_attributes.get_stat(&val);},
-            None => {},
-        }
-        match base_val {
-            Ok(val) => val.value + skill_auto_bonus_prog + self.proficiency.get_bonus(character.level),
-            Err(err) => {log!("{err}"); return -99},
+        match self.calculate_stat_breakdown(character) {
+            Ok(breakdown) => breakdown.total,
+            Err(err) => {log!("{err}"); -99},
         }
     }
+
+    pub fn calculate_stat_breakdown(self: &Self, character: &Character) -> Result<StatBreakdown, String> {
+        self.calculate_stat_breakdown_with(character, &[])
+    }
+
+    /// Breakdown with extra selectors for modifiers that only apply to a specific use of the stat, e.g. "initiative"
+    pub fn calculate_stat_breakdown_with(self: &Self, character: &Character, extra_selectors: &[String]) -> Result<StatBreakdown, String> {
+        character.calculate_stat_breakdown(self, extra_selectors)
+    }
 }
 
 impl PartialEq for CalculatedStat {
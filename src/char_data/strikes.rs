@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+
+use super::{character::Character, creature::Creature, gear::{ExtraDamage, Gear, GearType, WeaponInfo, WeaponRunes, WeaponType}, modifiers::{Modifier, ModifierType, StatBreakdown}, proficiency::ProficiencyLevel, traits};
+
+/// Penalty of every attack after the first in a turn
+pub const MAP_STEP: i32 = 5;
+pub const AGILE_MAP_STEP: i32 = 4;
+pub const ATTACKS_PER_TURN: i32 = 3;
+/// Attacks are possible up to six range increments, each increment after the first gives this penalty
+pub const MAX_RANGE_INCREMENTS: i32 = 6;
+pub const RANGE_INCREMENT_PENALTY: i32 = 2;
+pub const VOLLEY_PENALTY: i32 = 2;
+pub const WEAPON_SPECIALIZATION_OPTION: &str = "weapon-specialization";
+pub const GREATER_WEAPON_SPECIALIZATION_OPTION: &str = "greater-weapon-specialization";
+/// Roll option giving access to the critical specialization of every weapon group, "critical-specialization-<group>" gives it for one group
+pub const CRITICAL_SPECIALIZATION_OPTION: &str = "critical-specialization";
+
+/// Damage weapon specialization adds for a proficiency rank, greater weapon specialization doubles it
+pub fn get_specialization_damage(proficiency: &ProficiencyLevel, greater: bool) -> i32 {
+    let damage = match proficiency {
+        ProficiencyLevel::Expert => 2,
+        ProficiencyLevel::Master => 3,
+        ProficiencyLevel::Legendary => 4,
+        _ => 0,
+    };
+    if greater {damage * 2} else {damage}
+}
+
+/// Proficiency every unarmed attack uses
+pub const UNARMED_PROFICIENCY: &str = "Unarmed";
+/// Item penalty to attacks with weapons that have the Improvised trait
+pub const IMPROVISED_PENALTY: i32 = 2;
+
+fn default_unarmed_group() -> String {
+    String::from("Brawling")
+}
+
+/// An unarmed attack like a fist or the jaws of some heritages
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UnarmedAttack {
+    pub name: String,
+    pub damage: i32,
+    pub d_type: String,
+    #[serde(default)]
+    pub traits: Vec<String>,
+    #[serde(default = "default_unarmed_group")]
+    pub group: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl UnarmedAttack {
+    /// The fist every character has
+    pub fn fist() -> UnarmedAttack {
+        UnarmedAttack {
+            name: String::from("Fist"),
+            damage: 4,
+            d_type: String::from("B"),
+            traits: ["Agile", "Finesse", "Nonlethal", "Unarmed"].into_iter().map(String::from).collect(),
+            group: default_unarmed_group(),
+            description: String::from(""),
+        }
+    }
+
+    /// The attack as a weapon so it can be used for strikes like any other weapon
+    pub fn to_gear(&self) -> Gear {
+        Gear {
+            name: self.name.clone(),
+            g_type: GearType::Weapon,
+            traits: self.traits.clone(),
+            proficiency: Some(String::from(UNARMED_PROFICIENCY)),
+            invested: None,
+            description: self.description.clone(),
+            weap_info: Some(WeaponInfo {
+                damage: self.damage,
+                w_type: WeaponType::Melee,
+                d_type: self.d_type.clone(),
+                group: self.group.clone(),
+                range: None,
+                reload: None,
+                ammunition: None,
+                runes: WeaponRunes::default(),
+            }),
+            armor_info: None,
+            worn: false,
+            quantity: None,
+            rules: vec![],
+        }
+    }
+}
+
+/// Effect of a critical hit with a weapon of the group, for characters with access to the critical specialization
+pub fn get_critical_specialization(group: &str) -> Option<&'static str> {
+    let effect = match group.to_lowercase().as_str() {
+        "axe" => "Choose a creature adjacent to the initial target and within reach. If its AC is lower than your attack roll result for the critical hit, you deal damage to that creature equal to the result of the weapon damage die you rolled (including extra dice for its potency rune, if any).",
+        "bomb" => "Increase the radius of the bomb's splash damage to 10 feet.",
+        "bow" => "If the target of the critical hit is adjacent to a surface, it gets stuck to that surface by the missile. The target is immobilized and must spend an Interact action to attempt a DC 10 Athletics check to pull the missile free.",
+        "brawling" => "The target must succeed at a Fortitude save against your class DC or be slowed 1 until the end of your next turn.",
+        "club" => "You knock the target away from you up to 10 feet (you choose the distance). This is forced movement.",
+        "crossbow" => "The target takes 1d8 persistent bleed damage. You gain an item bonus to this bleed damage equal to the weapon's item bonus to attack rolls.",
+        "dart" | "knife" => "The target takes 1d6 persistent bleed damage. You gain an item bonus to this bleed damage equal to the weapon's item bonus to attack rolls.",
+        "firearm" | "sling" => "The target must succeed at a Fortitude save against your class DC or be stunned 1.",
+        "flail" | "hammer" => "The target is knocked prone.",
+        "pick" => "The weapon viciously pierces the target, who takes 2 additional damage per weapon damage die.",
+        "polearm" => "The target is moved 5 feet in a direction of your choice. This is forced movement.",
+        "shield" => "You knock the target back from you 5 feet. This is forced movement.",
+        "spear" => "The weapon pierces the target, weakening its attacks. The target is clumsy 1 until the start of your next turn.",
+        "sword" => "The target is made off-balance by your attack, becoming off-guard until the start of your next turn.",
+        _ => return None,
+    };
+    Some(effect)
+}
+
+/// One way of dealing the weapon's damage, e.g. wielded in two hands or with a versatile damage type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DamageProfile {
+    pub name: String,
+    pub dice: i32,
+    pub die_size: i32,
+    pub bonus: StatBreakdown,
+    pub d_type: String,
+    /// Damage from property runes and similar sources
+    pub extra: Vec<ExtraDamage>,
+}
+
+impl DamageProfile {
+    pub fn get_text(&self) -> String {
+        let bonus = self.bonus.total;
+        let bonus_text = if bonus != 0 {format!(" {bonus:+}")} else {String::from("")};
+        let extra_text: String = self.extra.iter().map(|extra| format!(" + {0}", extra.get_text())).collect();
+        format!("{0}d{1}{bonus_text} {2}{extra_text}", self.dice, self.die_size, self.d_type)
+    }
+}
+
+/// A weapon attack with its attack bonus and every damage profile it can be used with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strike {
+    pub name: String,
+    pub attack: StatBreakdown,
+    pub map_step: i32,
+    pub profiles: Vec<DamageProfile>,
+    pub traits: Vec<String>,
+    /// Die size of the Deadly trait
+    pub deadly: Option<i32>,
+    /// Die size of the Fatal trait
+    pub fatal: Option<i32>,
+    /// Critical specialization effect, only set if the character has access to it
+    pub critical_specialization: Option<String>,
+    pub range_increment: Option<i32>,
+    pub reload: Option<i32>,
+    /// Distance of the Volley trait, attacks at or below it take a penalty
+    pub volley: Option<i32>,
+    pub ammunition: Option<String>,
+}
+
+impl Strike {
+    /// Builds the strike of a weapon. Melee and thrown weapons add strength to damage, propulsive weapons half of it.
+    /// Melee weapons attack with strength, ranged ones with dexterity and finesse weapons with the higher of both.
+    /// Potency and striking runes only count without automatic bonus progression, the potency is added by `Character::get_modifiers`
+    pub fn from_weapon(weapon: &Gear, character: &Character) -> Result<Strike, String> {
+        let weapon_name = weapon.name.clone();
+        if weapon.g_type != GearType::Weapon {
+            return Err(format!("{weapon_name} is not a weapon"));
+        }
+        let weap_info = weapon.weap_info.as_ref().ok_or(format!("{weapon_name} does not have weapon info"))?;
+        let prof_name = weapon.proficiency.as_ref().ok_or(format!("{weapon_name} does not have a proficiency"))?;
+        let mut stat = character.get_prof_obj_from_name(prof_name).ok_or(format!("Could not find a proficiency with name {prof_name}"))?;
+        let is_melee = weap_info.w_type == WeaponType::Melee;
+        let str_value = character.get_attribute_val("str")?;
+        let dex_value = character.get_attribute_val("dex")?;
+
+        stat.attribute = String::from(
+            if !is_melee || (weapon.has_trait("Finesse") && dex_value > str_value) {"dex"} else {"str"}
+        );
+        let lower_name = weapon_name.to_lowercase();
+        let range_selector = String::from(if is_melee {"melee-attack"} else {"ranged-attack"});
+        let attack = stat.calculate_stat_breakdown_with(character, &[format!("{lower_name}-attack"), range_selector])?;
+
+        let is_thrown = weapon.has_trait("Thrown");
+        let str_damage = if is_melee || is_thrown {
+            Some(Modifier::new("Strength", ModifierType::Ability, str_value))
+        }
+        else if weapon.has_trait("Propulsive") {
+            let value = if str_value > 0 {str_value / 2} else {str_value};
+            Some(Modifier::new("Strength (Propulsive)", ModifierType::Ability, value))
+        }
+        else {
+            None
+        };
+        let mut damage_modifiers: Vec<Modifier> = str_damage.into_iter().collect();
+        if character.has_roll_option(WEAPON_SPECIALIZATION_OPTION) {
+            let greater = character.has_roll_option(GREATER_WEAPON_SPECIALIZATION_OPTION);
+            let source = if greater {"Greater Weapon Specialization"} else {"Weapon Specialization"};
+            let specialization = get_specialization_damage(&stat.proficiency, greater);
+            if specialization != 0 {
+                damage_modifiers.push(Modifier::new(source, ModifierType::Untyped, specialization));
+            }
+        }
+        let damage_selectors = vec![String::from("damage"), format!("{lower_name}-damage")];
+        damage_modifiers.extend(character.get_modifiers(&damage_selectors));
+        let bonus = StatBreakdown::new(0, damage_modifiers).with_notes(character.get_notes(&damage_selectors));
+
+        let dice = if character.variants.automatic_bonus_progression {
+            std::cmp::max(character.abp_data.attack_dice, 1)
+        }
+        else {
+            1 + weap_info.runes.striking
+        };
+        let base = DamageProfile {
+            name: String::from(if is_melee {"Melee"} else {"Ranged"}),
+            dice,
+            die_size: weap_info.damage,
+            bonus,
+            d_type: weap_info.d_type.trim().to_string(),
+            extra: weap_info.runes.property.iter().filter_map(|rune| rune.damage.clone()).collect(),
+        };
+        let mut profiles = vec![];
+        if let Some(die_size) = weapon.trait_die("Two-Hand") {
+            profiles.push(DamageProfile {name: String::from("Two-Hand"), die_size, ..base.clone()});
+        }
+        for d_type in traits::trait_values(&weapon.traits, "Versatile") {
+            profiles.push(DamageProfile {name: format!("Versatile {d_type}"), d_type, ..base.clone()});
+        }
+        profiles.insert(0, base);
+
+        let group = weap_info.group.to_lowercase();
+        let has_critical_specialization = !group.is_empty()
+            && (character.has_roll_option(CRITICAL_SPECIALIZATION_OPTION) || character.has_roll_option(&format!("{CRITICAL_SPECIALIZATION_OPTION}-{group}")));
+        let critical_specialization = if has_critical_specialization {
+            get_critical_specialization(&group).map(String::from)
+        }
+        else {
+            None
+        };
+
+        Ok(Strike {
+            name: weapon_name,
+            attack,
+            map_step: if weapon.has_trait("Agile") {AGILE_MAP_STEP} else {MAP_STEP},
+            profiles,
+            traits: weapon.traits.clone(),
+            range_increment: weap_info.range.or_else(|| traits::find_trait(&weapon.traits, "Thrown").and_then(|t| t.get_number())),
+            reload: weap_info.reload,
+            volley: traits::find_trait(&weapon.traits, "Volley").and_then(|t| t.get_number()),
+            ammunition: weap_info.ammunition.clone(),
+            deadly: weapon.trait_die("Deadly"),
+            fatal: weapon.trait_die("Fatal"),
+            critical_specialization,
+        })
+    }
+
+    /// Damage of a critical hit with a profile: everything is doubled, Fatal raises the die size and adds one die,
+    /// Deadly adds one die, two with greater striking and three with major striking
+    pub fn get_critical_text(&self, profile: &DamageProfile) -> String {
+        let doubled = match self.fatal {
+            Some(die_size) => DamageProfile {die_size, ..profile.clone()},
+            None => profile.clone(),
+        };
+        let mut text = format!("2 × ({0})", doubled.get_text());
+        if let Some(die_size) = self.fatal {
+            text.push_str(&format!(" + 1d{die_size} {0}", profile.d_type));
+        }
+        if let Some(die_size) = self.deadly {
+            let dice = std::cmp::max(profile.dice - 1, 1);
+            text.push_str(&format!(" + {dice}d{die_size} {0}", profile.d_type));
+        }
+        text
+    }
+
+    /// Attack breakdown of the nth attack of a turn starting at 0, with the multiple attack penalty
+    pub fn get_attack_breakdown(&self, attack: i32) -> StatBreakdown {
+        let mut breakdown = self.attack.clone();
+        let penalty = attack * self.map_step;
+        if penalty != 0 {
+            breakdown.applied.push(Modifier::new("Multiple Attack Penalty", ModifierType::Untyped, -penalty));
+            breakdown.total -= penalty;
+        }
+        breakdown
+    }
+
+    /// Attack bonus of the first, second and third attack of a turn
+    pub fn get_attack_bonuses(&self) -> Vec<i32> {
+        (0..ATTACKS_PER_TURN).map(|attack| self.get_attack_breakdown(attack).total).collect()
+    }
+
+    /// Breakdown of the first attack at the greatest distance of every range increment, empty for weapons without a range.
+    /// Attacks at or below the Volley distance take its penalty, so that distance gets an entry of its own
+    pub fn get_range_breakdowns(&self) -> Vec<(i32, StatBreakdown)> {
+        let Some(range) = self.range_increment else {
+            return vec![];
+        };
+        let mut distances: Vec<i32> = (1..=MAX_RANGE_INCREMENTS).map(|increment| range * increment).collect();
+        if let Some(volley) = self.volley.filter(|volley| *volley > 0 && *volley < range * MAX_RANGE_INCREMENTS && volley % range != 0) {
+            distances.push(volley);
+            distances.sort();
+        }
+        distances.into_iter().map(|distance| {
+            let mut breakdown = self.attack.clone();
+            let increment = (distance + range - 1) / range;
+            let mut penalties = vec![];
+            if increment > 1 {
+                penalties.push(Modifier::new(&format!("Range increment {increment}"), ModifierType::Untyped, -(increment - 1) * RANGE_INCREMENT_PENALTY));
+            }
+            if self.volley.is_some_and(|volley| distance <= volley) {
+                penalties.push(Modifier::new("Volley", ModifierType::Untyped, -VOLLEY_PENALTY));
+            }
+            for penalty in penalties {
+                breakdown.total += penalty.value;
+                breakdown.applied.push(penalty);
+            }
+            (distance, breakdown)
+        }).collect()
+    }
+}
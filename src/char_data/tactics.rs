@@ -1,6 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::rules::RuleElement;
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Tactic {
@@ -9,4 +11,6 @@ pub struct Tactic {
     pub selected: bool,
     pub traits: Vec<String>,
     pub description: String,
+    #[serde(default)]
+    pub rules: Vec<RuleElement>,
 }
\ No newline at end of file
@@ -1,7 +1,103 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
+/// A trait like "Deadly d8" split into its name and parameter. Traits are stored as strings and parsed when queried
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Trait {
     pub name: String,
-    pub description: String
-}
\ No newline at end of file
+    pub parameter: Option<String>
+}
+
+impl Trait {
+    /// Splits off a trailing parameter, which starts at the first word that is a die, a number, a one letter damage type
+    /// or lower case, e.g. "Thrown 20 ft.", "Versatile P" or "Attached to shield". Multi-word names like "Fatal Aim d12" stay whole
+    pub fn parse(text: &str) -> Trait {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.iter().skip(1).position(|word| is_parameter_start(word)) {
+            Some(position) => Trait {
+                name: words[..=position].join(" "),
+                parameter: Some(words[position + 1..].join(" ")),
+            },
+            None => Trait {name: words.join(" "), parameter: None},
+        }
+    }
+
+    /// Die size of a parameter like "d10"
+    pub fn get_die(&self) -> Option<i32> {
+        self.parameter.as_ref()?.strip_prefix('d')?.parse().ok()
+    }
+
+    /// Leading number of a parameter like "20 ft." or "1"
+    pub fn get_number(&self) -> Option<i32> {
+        let digits: String = self.parameter.as_ref()?.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse().ok()
+    }
+}
+
+fn is_parameter_start(word: &str) -> bool {
+    let is_die = word.strip_prefix('d').is_some_and(|size| !size.is_empty() && size.chars().all(|c| c.is_ascii_digit()));
+    let is_damage_type = word.len() == 1 && word.chars().all(|c| c.is_ascii_uppercase());
+    let starts_lower_or_digit = word.chars().next().is_some_and(|c| c.is_lowercase() || c.is_ascii_digit());
+    is_die || is_damage_type || starts_lower_or_digit
+}
+
+impl fmt::Display for Trait {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.parameter {
+            Some(parameter) => write!(f, "{0} {parameter}", self.name),
+            None => write!(f, "{0}", self.name),
+        }
+    }
+}
+
+pub fn parse_traits(traits: &[String]) -> Vec<Trait> {
+    traits.iter().map(|t| Trait::parse(t)).collect()
+}
+
+pub fn find_trait(traits: &[String], name: &str) -> Option<Trait> {
+    traits.iter().map(|t| Trait::parse(t)).find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+pub fn has_trait(traits: &[String], name: &str) -> bool {
+    find_trait(traits, name).is_some()
+}
+
+/// Parameter of the trait, None if the trait is missing or has no parameter
+pub fn trait_value(traits: &[String], name: &str) -> Option<String> {
+    find_trait(traits, name)?.parameter
+}
+
+/// Parameters of every trait with this name, e.g. both damage types of "Versatile P" and "Versatile S"
+pub fn trait_values(traits: &[String], name: &str) -> Vec<String> {
+    parse_traits(traits).into_iter().filter(|t| t.name.eq_ignore_ascii_case(name)).filter_map(|t| t.parameter).collect()
+}
+
+pub fn trait_die(traits: &[String], name: &str) -> Option<i32> {
+    find_trait(traits, name)?.get_die()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_die_parameters() {
+        for (text, name, die) in [("Deadly d8", "Deadly", 8), ("Jousting d6", "Jousting", 6), ("Fatal Aim d12", "Fatal Aim", 12)] {
+            let parsed = Trait::parse(text);
+            assert_eq!(parsed.name, name);
+            assert_eq!(parsed.get_die(), Some(die));
+            assert_eq!(parsed.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn parses_damage_type_and_number_parameters() {
+        let traits = vec![String::from("Versatile P"), String::from("Versatile S"), String::from("Thrown 20 ft."), String::from("Agile")];
+        assert_eq!(trait_value(&traits, "versatile"), Some(String::from("P")));
+        assert_eq!(trait_values(&traits, "Versatile"), vec![String::from("P"), String::from("S")]);
+        assert_eq!(find_trait(&traits, "Thrown").and_then(|t| t.get_number()), Some(20));
+        assert_eq!(Trait::parse("Agile"), Trait {name: String::from("Agile"), parameter: None});
+        assert!(!has_trait(&traits, "Deadly"));
+    }
+}
@@ -5,9 +5,10 @@ use std::{collections::HashMap, error::Error};
 use std::fs::read_to_string;
 use std::path::Path;
 use crate::char_data::character::{Character, SimpleCharacter};
+use crate::char_data::stat_catalog::StatCatalog;
 
 
-pub fn read_char_from_file<P: AsRef<Path>>(path: P) -> Result<Character, Box<dyn Error>> {
+pub fn read_char_from_file<P: AsRef<Path>>(path: P, catalog: &StatCatalog) -> Result<Character, Box<dyn Error>> {
 
     // Open the file in read-only mode with buffer.
     let check_file_path_result = std::fs::exists(&path);
@@ -16,11 +17,11 @@ pub fn read_char_from_file<P: AsRef<Path>>(path: P) -> Result<Character, Box<dyn
             if exists {
                 let file_str = read_to_string(&path)?;
                 let character: SimpleCharacter = serde_json::from_str(&file_str)?;
-                return Ok(Character::from(character));
+                Ok(Character::from_simple(&character, catalog)?)
             }
             else {
                 log!("Filepath does not exist, return new char");
-                return Ok(Character::zero());
+                Ok(Character::zero(catalog))
             }
         },
         Err(error) => {
@@ -60,6 +61,31 @@ pub fn read_vector_from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P, debug
 
 }
 
+pub fn read_object_from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P, debug_name: &str) -> Result<T, Box<dyn Error>> {
+
+    // Open the file in read-only mode with buffer.
+    let check_file_path_result = std::fs::exists(&path);
+    match check_file_path_result {
+        Ok(exists) => {
+            if exists {
+                let file_str = read_to_string(&path)?;
+                let object: T = serde_json::from_str(&file_str)?;
+                Ok(object)
+            }
+            else {
+                let errorstring = format!("Filepath does not exist: Cannot build {debug_name}");
+                log!("{errorstring}");
+                Err(Box::from(errorstring))
+            }
+        },
+        Err(error) => {
+            let errorstring = format!("There was an issue locating the path, this might be due to accessing rights. Cannot confirm or deny existence:\n{error}");
+            log!("{errorstring}");
+            Err(Box::from(error))
+        },
+    }
+}
+
 pub fn read_map_from_file<T: DeserializeOwned + Eq + Hash, D: DeserializeOwned + Eq + Hash, P: AsRef<Path>>(path: P, debug_name: &str) -> Result<HashMap<T, D>, Box<dyn Error>> {
 
     // Open the file in read-only mode with buffer.
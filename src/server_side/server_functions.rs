@@ -1,19 +1,37 @@
 use leptos::*;
 
+use crate::char_data::ancestries::AncestryData;
+use crate::char_data::backgrounds::BackgroundData;
+use crate::char_data::classes::ClassData;
+use crate::char_data::companion::CompanionCatalog;
 use crate::char_data::conditions::Condition;
 use crate::char_data::feats::Feat;
+use crate::char_data::spells::Spell;
+use crate::char_data::stat_catalog::StatCatalog;
 use crate::server_side::read_json::{read_char_from_file, write_char_to_file, read_vector_from_file};
 use crate::char_data::character::Character;
 use std::collections::HashMap;
 use std::process::Command;
 
-use super::read_json::read_map_from_file;
+use super::read_json::{read_map_from_file, read_object_from_file};
 
 #[server(GetChar, "/api", "GetJson", "get_char")]
 pub async fn get_char() -> Result<Character, ServerFnError> {
-    let read_char_result = read_char_from_file("saves/char.json");
+    let catalog = get_stat_catalog().await?;
+    let ancestries = get_ancestries().await?;
+    let classes = get_classes().await?;
+    let conditions = get_conditions().await?;
+    let companions = get_companions().await?;
+    let feat_map: HashMap<String, Feat> = get_feats().await?.into_iter().map(|feat| (feat.name.clone(), feat)).collect();
+    let read_char_result = read_char_from_file("saves/char.json", &catalog);
     match read_char_result {
-        Ok(read_char) => return Ok(read_char),
+        Ok(mut read_char) => {
+            read_char.sync_origins(&ancestries, &classes).map_err(ServerFnError::new)?;
+            read_char.sync_feat_additions(&feat_map, &catalog).map_err(ServerFnError::new)?;
+            read_char.sync_granted_conditions(&conditions).map_err(ServerFnError::new)?;
+            read_char.sync_companions(&companions).map_err(ServerFnError::new)?;
+            Ok(read_char)
+        },
         Err(error) => return Err(ServerFnError::new(error.to_string())),
     }
 }
@@ -28,6 +46,15 @@ pub async fn set_char(char: Character) -> Result<i32, ServerFnError> {
 }
 
 
+#[server(GetStatCatalog, "/api", "GetJson", "skills")]
+pub async fn get_stat_catalog() -> Result<StatCatalog, ServerFnError> {
+    let read_catalog_result = read_object_from_file::<StatCatalog,_>("resources/skills.json", "Skill catalog");
+    match read_catalog_result {
+        Ok(catalog) => Ok(catalog),
+        Err(error) => Err(ServerFnError::new(error.to_string())),
+    }
+}
+
 #[server(GetConditions, "/api", "GetJson", "conditions")]
 pub async fn get_conditions() -> Result<Vec<Condition>, ServerFnError> {
     let read_cond_result = read_vector_from_file::<Condition,_>("resources/conditions.json", "Condition");
@@ -46,6 +73,51 @@ pub async fn get_feats() -> Result<Vec<Feat>, ServerFnError> {
     }
 }
 
+#[server(GetClasses, "/api", "GetJson", "classes")]
+pub async fn get_classes() -> Result<Vec<ClassData>, ServerFnError> {
+    let read_class_result = read_vector_from_file::<ClassData,_>("resources/classes.json", "Class");
+    match read_class_result {
+        Ok(classes) => Ok(classes),
+        Err(error) => Err(ServerFnError::new(error.to_string())),
+    }
+}
+
+#[server(GetAncestries, "/api", "GetJson", "ancestries")]
+pub async fn get_ancestries() -> Result<Vec<AncestryData>, ServerFnError> {
+    let read_ancestry_result = read_vector_from_file::<AncestryData,_>("resources/ancestries.json", "Ancestry");
+    match read_ancestry_result {
+        Ok(ancestries) => Ok(ancestries),
+        Err(error) => Err(ServerFnError::new(error.to_string())),
+    }
+}
+
+#[server(GetBackgrounds, "/api", "GetJson", "backgrounds")]
+pub async fn get_backgrounds() -> Result<Vec<BackgroundData>, ServerFnError> {
+    let read_background_result = read_vector_from_file::<BackgroundData,_>("resources/backgrounds.json", "Background");
+    match read_background_result {
+        Ok(backgrounds) => Ok(backgrounds),
+        Err(error) => Err(ServerFnError::new(error.to_string())),
+    }
+}
+
+#[server(GetSpells, "/api", "GetJson", "spells")]
+pub async fn get_spells() -> Result<Vec<Spell>, ServerFnError> {
+    let read_spell_result = read_vector_from_file::<Spell,_>("resources/spells.json", "Spell");
+    match read_spell_result {
+        Ok(spells) => Ok(spells),
+        Err(error) => Err(ServerFnError::new(error.to_string())),
+    }
+}
+
+#[server(GetCompanions, "/api", "GetJson", "companions")]
+pub async fn get_companions() -> Result<CompanionCatalog, ServerFnError> {
+    let read_companion_result = read_object_from_file::<CompanionCatalog,_>("resources/companions.json", "Companion catalog");
+    match read_companion_result {
+        Ok(companions) => Ok(companions),
+        Err(error) => Err(ServerFnError::new(error.to_string())),
+    }
+}
+
 #[server(GetTraits, "/api", "GetJson", "traits")]
 pub async fn get_traits() -> Result<HashMap<String, String>, ServerFnError> {
     let read_trait_result = read_map_from_file::<String, String, _>("resources/traits.json", "Feat");
@@ -56,6 +128,16 @@ pub async fn get_traits() -> Result<HashMap<String, String>, ServerFnError> {
 }
 
 
+/// Rolls a die with the given number of sides on the server
+#[server(RollDie, "/api", "Url", "roll_die")]
+pub async fn roll_die(sides: i32) -> Result<i32, ServerFnError> {
+    use rand::Rng;
+    if sides < 1 {
+        return Err(ServerFnError::new(format!("A die needs at least one side, got {sides}")));
+    }
+    Ok(rand::thread_rng().gen_range(1..=sides))
+}
+
 #[server(PingServer, "/api", "GetJson", "ping")]
 pub async fn ping_server() -> Result<i32, ServerFnError> {
     Ok(0)
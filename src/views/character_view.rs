@@ -1,15 +1,27 @@
 use std::collections::HashMap;
 
 use crate::char_data::character::*;
+use crate::char_data::ancestries::AncestryData;
+use crate::char_data::backgrounds::BackgroundData;
+use crate::char_data::classes::ClassData;
+use crate::char_data::companion::CompanionCatalog;
+use crate::char_data::stat_catalog::StatCatalog;
 use crate::char_data::conditions::Condition;
+use crate::char_data::creature::Creature;
 use crate::char_data::feats::Feat;
-use crate::char_data::proficiency::ProficiencyLevel;
+use crate::char_data::movement::MovementType;
+use crate::char_data::spells::Spell;
 use crate::char_data::stats::ProficiencyType;
 use crate::error_template::SheetError;
 use crate::server_side::server_functions::*;
 use super::view_helpers::*;
 use super::stats_views::*;
 use super::equip_views::*;
+use super::condition_view::ConditionView;
+use super::creation_view::CreationView;
+use super::level_up_view::LevelUpView;
+use super::spell_view::SpellView;
+use super::companion_view::CompanionSection;
 
 use leptos::*;
 use leptos::logging::log;
@@ -19,7 +31,13 @@ pub fn BaseView(
     char: Character,
     feats: Vec<Feat>,
     conditions: Vec<Condition>,
-    trait_data: HashMap<String, String>
+    trait_data: HashMap<String, String>,
+    classes: Vec<ClassData>,
+    stat_catalog: StatCatalog,
+    ancestries: Vec<AncestryData>,
+    backgrounds: Vec<BackgroundData>,
+    spells: Vec<Spell>,
+    companions: CompanionCatalog
 ) -> impl IntoView {
     //log!("Char on init {char:#?}");
     let (read_ketra, write_ketra) = create_signal(char);
@@ -49,11 +67,21 @@ pub fn BaseView(
     provide_context(write_ketra);
     provide_context(conditions.clone());
     provide_context(trait_data.clone());
+    provide_context(classes.clone());
+    provide_context(stat_catalog.clone());
+    provide_context(ancestries.clone());
+    provide_context(backgrounds.clone());
+    provide_context(spells.clone());
+    provide_context(companions.clone());
     let feat_map: HashMap<String, Feat> = feats.into_iter().map(|feat: Feat| (feat.name.clone(), feat)).collect();
     provide_context(feat_map);
     view!{
-        <CharView/>
-        <HorseSection/>
+        <Show when=move || read_ketra.with(|c| !c.name.is_empty())
+            fallback=|| view! {<CreationView/>}
+        >
+            <CharView/>
+            <CompanionSection/>
+        </Show>
     }
 }
 
@@ -82,6 +110,7 @@ pub fn CharView() -> impl IntoView {
         <div class="flex-row space-between">
             <ProficiencySidebar/>
             <section class="flex-col center-col-layout">
+                <ConditionView/>
                 <textarea 
                     class="center-text-area" 
                     id="test"
@@ -94,6 +123,7 @@ pub fn CharView() -> impl IntoView {
                     }
                     prop:value={move || read_ketra.with(|c| c.text.clone())}
                 />
+                <SpellView/>
             </section>
             <section class="flex-col equip-section">
                 <EquipView/>
@@ -101,6 +131,7 @@ pub fn CharView() -> impl IntoView {
             <section class="flex-col right-side-col">
                 <TacticsView/>
                 <FeatView/>
+                <FocusView/>
             </section>
         </div>
     }
@@ -109,27 +140,48 @@ pub fn CharView() -> impl IntoView {
 #[component]
 pub fn TopCharViewSection() -> impl IntoView {
     let (read_ketra, write_ketra) = get_base_context("TopCharView");
+    let sheet_error = get_sheet_error_context("TopCharView");
+    let feat_map = use_context::<HashMap<String, Feat>>().expect("TopCharView: Expected feat map to be set");
+    let catalog = use_context::<StatCatalog>().expect("TopCharView: Expected stat catalog to be set");
+    let companions = use_context::<CompanionCatalog>().expect("TopCharView: Expected companion catalog to be set");
+    let show_level_up = create_rw_signal(false);
+    let level_down = move |_| {
+        let mut result = Ok(());
+        write_ketra.update(|c| result = c.level_down()
+            .and_then(|_| c.sync_feat_additions(&feat_map, &catalog))
+            .and_then(|_| c.sync_companions(&companions)));
+        if let Err(err) = result {
+            log!("TopCharView: {err}");
+            sheet_error.set(SheetError::new(&err));
+        }
+    };
     view!{
         <div id="top_div" class="flex-row flex-wrap no-grow-children">
             <section>
                 <div class="flex-row align-center no-grow-children">   
                     <button
-                        on:click=move |_| {write_ketra.update(move |c| {
-                            c.level += 1;
-                            c.hp_info.calculate_max_hp(c.level, c.attributes.get_stat_val("con").expect("There should be a con stat"));
-                            c.horse_hp_info.calculate_max_hp(c.level, 2);
-                        })}
-                        on:contextmenu=move |_| {write_ketra.update(move |c| {
-                            c.level -= 1;
-                            c.hp_info.calculate_max_hp(c.level, c.attributes.get_stat_val("con").expect("There should be a con stat"));
-                            c.horse_hp_info.calculate_max_hp(c.level, 2);
-                        })}
+                        on:click=move |_| show_level_up.update(|show| *show = !*show)
+                        on:contextmenu=level_down
                     >
                         Level {move || read_ketra.with(|k| k.level)}
                     </button>
-                    <div>SIZE<br/>Medium</div>
-                    <div>SPEED<br/>30ft.</div>
+                    <div title=move || read_ketra.with(|c| c.heritage.clone())>
+                        {move || read_ketra.with(|c| format!("{0} {1}", c.ancestry, c.class))}<br/>{move || read_ketra.with(|c| c.background.clone())}
+                    </div>
+                    <KeyAttributeView/>
+                    <div>SIZE<br/>{move || read_ketra.with(|c| c.size.to_string())}</div>
+                    <MovementView/>
+                    <label title="Automatic bonus progression replaces potency, striking and resilient runes">
+                        <input type="checkbox"
+                            prop:checked=move || read_ketra.with(|c| c.variants.automatic_bonus_progression)
+                            on:change=move |event| write_ketra.update(|c| c.variants.automatic_bonus_progression = event_target_checked(&event))
+                        />
+                        ABP
+                    </label>
                 </div>
+                <Show when=move || show_level_up.get()>
+                    <LevelUpView show=show_level_up/>
+                </Show>
             </section>
             <section class="align-center">
                 <DefenseView/>
@@ -138,7 +190,7 @@ pub fn TopCharViewSection() -> impl IntoView {
                 <MainStatsView/>
             </section>
             <section class="align-center" id="hp_section">
-                <HpView horse=false/>
+                <HpView companion=None/>
             </section>
             <section class="align-center" id="shield_section">
                 <ShieldView/>
@@ -147,28 +199,79 @@ pub fn TopCharViewSection() -> impl IntoView {
     }
 }
 
+#[component]
+pub fn MovementView() -> impl IntoView {
+    let (read_ketra, _) = get_base_context("MovementView");
+    let speed_view = move |movement: MovementType| {
+        let label = if movement == MovementType::Land {String::from("SPEED")} else {movement.to_string().to_uppercase()};
+        let get_breakdown = move || read_ketra.with(|c| c.calculate_speed_breakdown(&movement));
+        view!{
+            <Show when={let get_breakdown = get_breakdown.clone(); move || get_breakdown().is_some()}>
+                <div title={let get_breakdown = get_breakdown.clone(); move || get_breakdown().map(|b| b.get_tooltip()).unwrap_or_default()}>
+                    {label.clone()}<br/>{let get_breakdown = get_breakdown.clone(); move || get_breakdown().map(|b| b.total).unwrap_or(0)}ft.
+                </div>
+            </Show>
+        }
+    };
+    view!{
+        {
+            MovementType::all().into_iter().map(speed_view).collect::<Vec<_>>()
+        }
+    }
+}
+
+#[component]
+pub fn KeyAttributeView() -> impl IntoView {
+    let (read_ketra, write_ketra) = get_base_context("KeyAttributeView");
+    let classes = use_context::<Vec<ClassData>>().expect("KeyAttributeView: Expected class list to be set");
+    let classes_for_validation = classes.clone();
+    let classes_for_select = classes.clone();
+    let get_options = move || read_ketra.with(|c| {
+        classes
+            .iter()
+            .find(|class_data| class_data.name == c.class)
+            .map(|class_data| class_data.key_attributes.clone())
+            .unwrap_or_default()
+    });
+    let validation_error = move || read_ketra.with(|c| c.validate_key_attribute(&classes_for_validation).err());
+    view!{
+        <div class="flex-col">
+            KEY
+            <select name="key_attribute" id="key_attribute_select"
+                on:change=move |event| {
+                    let val = event_target_value(&event);
+                    write_ketra.update(|c| {
+                        if let Err(err) = c.set_key_attribute(&val, &classes_for_select) {
+                            log!("KeyAttributeView: {err}");
+                        }
+                    });
+                }
+            >
+                {
+                    move || get_options().into_iter().map(|attribute_id| {
+                        let selected = read_ketra.with(|c| c.key_attribute == attribute_id);
+                        view!{
+                            <option selected=selected value=attribute_id.clone()>{attribute_id.to_uppercase()}</option>
+                        }
+                    }).collect::<Vec<_>>()
+                }
+            </select>
+            {
+                move || validation_error().map(|err| view!{<p class="error tiny-text">{err}</p>})
+            }
+        </div>
+    }
+}
+
 #[component]
 pub fn ProficiencySidebar(
 ) -> impl IntoView {
-    let (read_char, _): (ReadSignal<Character>, WriteSignal<Character>) = get_base_context("ProficiencySidebar");
     let show_edit_stats = create_rw_signal(false);
-    let has_incr_init = move || {
-        read_char.with(|c| {
-            check_character_flag(c, "incred_init")
-        })
-    };
     view! {
         <section class="flex-col flex-wrap" style="flex-grow: 0; flex-shrink: 0">
             <b><SwitchProfView show_edit_stats=show_edit_stats types=vec![ProficiencyType::ClassDC]/></b>
             <b><SwitchProfView show_edit_stats=show_edit_stats types=vec![ProficiencyType::Perception]/></b>
-            <Show when=has_incr_init>
-                <div class="skill-grid">
-                    <div style="display:flex; flex: 1 0 0">initiative</div>
-                    <div></div>
-                    <div>+2</div>
-                
-                </div>
-            </Show>
+            <InitiativeView/>
             <div class="flex-col">
                 <h5>Saves</h5>
                 <SwitchProfView show_edit_stats=show_edit_stats types=vec![ProficiencyType::Save]/>
@@ -186,45 +289,16 @@ pub fn ProficiencySidebar(
                     <h5>Armor</h5>
                     <SwitchProfView show_edit_stats=show_edit_stats types=vec![ProficiencyType::Armor]/>
                 </div>
+                <div class="flex-col">
+                    <h5>Lores</h5>
+                    <LoreEditView/>
+                </div>
             </Show>
             <button on:click=move |_| show_edit_stats.update(|b| *b=!*b) style="justify-content:center">Edit</button>
         </section>
     }
 }
 
-#[component]
-pub fn HorseSection(
-) -> impl IntoView {
-    let (read_c, _) = get_base_context("HorseSection");
-    let get_bonus = move || {
-        let level = read_c.with(|c| c.level);
-        ProficiencyLevel::Trained.get_bonus(level)
-    };
-    let get_att = move || {
-        get_bonus() + 3
-    };
-    let get_ac = move || {
-        get_bonus() + 2 + 10
-    };
-    view! {
-        <div class="flex-row">
-            <section class="align-center">
-                <HpView horse=true/>
-            </section>
-            <section class="align-center">
-                <label>AC: {get_ac}</label>
-            </section>
-            <section class="align-center">
-                <label>Attack: {get_att}</label>
-            </section>
-        </div>
-        <img src="horse.png" style="display:flex"/>
-        <img src="support.png" style="display:flex"/>
-        <img src="mature_horse.png" style="display:flex"/>
-    }
-}
-
-
 /* <section class="flex-row flex-grow-1 flex-shrink" style="justify-content:center">
     {
         move || {
@@ -0,0 +1,211 @@
+use crate::char_data::companion::{Companion, CompanionCatalog};
+use crate::char_data::conditions::Condition;
+use crate::char_data::modifiers::StatBreakdown;
+use crate::char_data::movement::MovementType;
+use crate::char_data::stat_catalog::StatCatalog;
+use crate::error_template::SheetError;
+use super::stats_views::{HpView, TraitView};
+use super::view_helpers::{get_base_context, get_prefix, get_sheet_error_context};
+use leptos::*;
+use leptos::logging::log;
+
+fn breakdown_line(label: String, breakdown: StatBreakdown) -> impl IntoView {
+    view! {
+        <div class="skill-grid" title=breakdown.get_tooltip()>
+            <div style="display:flex; flex: 1 0 0">{label}</div>
+            <div></div>
+            <div>{format!("{0}{1}", get_prefix(breakdown.total), breakdown.total)}</div>
+        </div>
+    }
+}
+
+/// Every companion and minion of the character, each in its own collapsible section, and a form to add more
+#[component]
+pub fn CompanionSection() -> impl IntoView {
+    let (read_char, write_char) = get_base_context("CompanionSection");
+    let sheet_error = get_sheet_error_context("CompanionSection");
+    let catalog = use_context::<CompanionCatalog>().expect("CompanionSection: Expected companion catalog to be set");
+
+    let companion_count = create_memo(move |_| read_char.with(|c| c.companions.len()));
+    let new_name = create_rw_signal(String::from(""));
+    let new_type = create_rw_signal(String::from(""));
+    let type_options: Vec<String> = catalog.types.iter().map(|c_type| c_type.name.clone()).collect();
+    let add_companion = move |_| {
+        let (name, companion_type) = (new_name.get_untracked(), new_type.get_untracked());
+        if name.trim().is_empty() || companion_type.is_empty() {
+            return;
+        }
+        let mut result = Ok(());
+        write_char.update(|c| result = c.add_companion(name.trim(), &companion_type, &catalog));
+        match result {
+            Ok(_) => sheet_error.set(SheetError::new("")),
+            Err(err) => {log!("CompanionSection: {err}"); sheet_error.set(SheetError::new(&err))},
+        }
+    };
+
+    view! {
+        <div class="flex-col companion-section">
+            {move || (0..companion_count.get()).map(|indx| view! {<CompanionView indx=indx/>}).collect::<Vec<_>>()}
+            <div class="flex-row align-center">
+                <h4>Companion</h4>
+                <input type="text" placeholder="Name" on:change=move |event| new_name.set(event_target_value(&event))/>
+                <select on:change=move |event| new_type.set(event_target_value(&event))>
+                    <option value="" selected=true>-</option>
+                    {type_options.iter().map(|option| view! {<option value=option.clone()>{option.clone()}</option>}).collect::<Vec<_>>()}
+                </select>
+                <button on:click=add_companion>Add</button>
+            </div>
+        </div>
+    }
+}
+
+/// A single companion with stats computed from the companion catalog. Clicking the name collapses it, right clicking removes it
+#[component]
+pub fn CompanionView(
+    indx: usize
+) -> impl IntoView {
+    let (read_char, write_char) = get_base_context("CompanionView");
+    let sheet_error = get_sheet_error_context("CompanionView");
+    let catalog = use_context::<CompanionCatalog>().expect("CompanionView: Expected companion catalog to be set");
+    let stat_catalog = use_context::<StatCatalog>().expect("CompanionView: Expected stat catalog to be set");
+    let all_conditions = use_context::<Vec<Condition>>().expect("CompanionView: Expected condition list to be set");
+    let collapse = create_rw_signal(false);
+
+    let change_companion = {
+        let catalog = catalog.clone();
+        move |change: &dyn Fn(&mut Companion, i32, &CompanionCatalog) -> Result<(), String>| {
+            let mut result = Ok(());
+            write_char.update(|c| {
+                let level = c.level;
+                result = c.get_companion_mut(indx).and_then(|companion| change(companion, level, &catalog));
+            });
+            match result {
+                Ok(_) => sheet_error.set(SheetError::new("")),
+                Err(err) => {log!("CompanionView: {err}"); sheet_error.set(SheetError::new(&err))},
+            }
+        }
+    };
+
+    let advancement_options: Vec<String> = catalog.advancements.iter().map(|advancement| advancement.name.clone()).collect();
+    let change_advancement = {
+        let change_companion = change_companion.clone();
+        move |event| {
+            let advancement = event_target_value(&event);
+            change_companion(&|companion, level, catalog| companion.set_advancement(&advancement, level, catalog));
+        }
+    };
+    let stats_view = {
+        let catalog = catalog.clone();
+        move || read_char.with(|c| c.companions.get(indx).map(|companion| -> Result<View, String> {
+            let companion_type = catalog.get_type(&companion.companion_type)?;
+            let size = companion.get_size(&catalog)?;
+            let proficiencies = companion.get_proficiencies(&catalog, &stat_catalog)?;
+            let mut lines = vec![breakdown_line(String::from("AC"), companion.calculate_ac_breakdown(c.level, &catalog, &stat_catalog)?).into_view()];
+            for prof in proficiencies.iter().filter(|prof| prof.name != "Unarmored" && prof.name != "Unarmed") {
+                lines.push(breakdown_line(prof.name.clone(), companion.calculate_stat_breakdown(prof, c.level, &catalog)?).into_view());
+            }
+            let speeds = MovementType::all()
+                .iter()
+                .filter_map(|movement| match companion.calculate_speed_breakdown(movement, c.level, &catalog) {
+                    Ok(breakdown) => breakdown.map(|breakdown| format!("{movement} {0}ft.", breakdown.total)),
+                    Err(err) => Some(err),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let strikes = companion.get_strikes(c.level, &catalog, &stat_catalog)?.into_iter().map(|strike| view! {
+                <div class="flex-col bright-bg">
+                    <div class="flex-row">
+                        <h4>{strike.name.clone()}</h4>
+                        <p title=strike.breakdown.get_tooltip()>{format!("{0}{1} {2}", get_prefix(strike.breakdown.total), strike.breakdown.total, strike.damage)}</p>
+                    </div>
+                    <TraitView trait_names=strike.traits.clone()/>
+                </div>
+            }).collect::<Vec<_>>();
+            let support_benefit = (!companion_type.support_benefit.is_empty())
+                .then(|| view! {<p class="tiny-text"><b>Support benefit </b>{companion_type.support_benefit.clone()}</p>});
+            let advanced_maneuver = (!companion_type.advanced_maneuver.is_empty())
+                .then(|| view! {<p class="tiny-text"><b>Advanced maneuver </b>{companion_type.advanced_maneuver.clone()}</p>});
+            let image = (!companion_type.image.is_empty()).then(|| view! {<img src=companion_type.image.clone() style="display:flex"/>});
+            Ok(view! {
+                <div class="flex-row">
+                    <div class="flex-col">
+                        <TraitView trait_names=companion_type.traits.clone()/>
+                        <p>{format!("{size}, {speeds}")}</p>
+                        {lines}
+                    </div>
+                    <div class="flex-col">
+                        {strikes}
+                        {support_benefit}
+                        {advanced_maneuver}
+                    </div>
+                    {image}
+                </div>
+            }.into_view())
+        }).map(|result| result.unwrap_or_else(|err| view! {<p class="error">{err}</p>}.into_view())))
+    };
+
+    let selected_condition = create_rw_signal(String::from(""));
+    let condition_options: Vec<String> = all_conditions.iter().map(|cond| cond.name.clone()).collect();
+    let add_condition = {
+        let change_companion = change_companion.clone();
+        let all_conditions = all_conditions.clone();
+        move |_| {
+            let name = selected_condition.get_untracked();
+            if !name.is_empty() {
+                change_companion(&|companion, _, _| companion.add_condition(&all_conditions, &name, None));
+            }
+        }
+    };
+    let condition_list = {
+        let change_companion = change_companion.clone();
+        let all_conditions = all_conditions.clone();
+        move || read_char.with(|c| c.companions.get(indx).map(|companion| companion.conditions.iter().map(|cond| {
+            let name = cond.name.clone();
+            let value_text = if cond.has_value {format!(" {0}", cond.get_value())} else {String::from("")};
+            let raise = {
+                let (change_companion, all_conditions, name) = (change_companion.clone(), all_conditions.clone(), name.clone());
+                move |_| change_companion(&|companion, _, _| {companion.change_condition_value(&all_conditions, &name, 1); Ok(())})
+            };
+            let lower = {
+                let (change_companion, all_conditions, name) = (change_companion.clone(), all_conditions.clone(), name.clone());
+                move |_| change_companion(&|companion, _, _| {companion.change_condition_value(&all_conditions, &name, -1); Ok(())})
+            };
+            view! {
+                <div class="bright-bg" class:implied-condition=!cond.explicit title=cond.description.clone() on:click=raise on:contextmenu=lower>
+                    {format!("{name}{value_text}")}
+                </div>
+            }
+        }).collect::<Vec<_>>()))
+    };
+
+    view! {
+        <div class="flex-col bright-bg">
+            <div class="flex-row align-center">
+                <h3 on:click=move |_| collapse.update(|c| *c = !*c) on:contextmenu=move |_| write_char.update(|c| c.remove_companion(indx))>
+                    {move || read_char.with(|c| c.companions.get(indx).map(|companion| format!("{0} ({1})", companion.name, companion.companion_type)))}
+                </h3>
+                <select on:change=change_advancement>
+                    {
+                        advancement_options.iter().map(|option| {
+                            let is_selected = {let option = option.clone(); move || read_char.with(|c| c.companions.get(indx).is_some_and(|companion| companion.advancement == option))};
+                            view! {<option value=option.clone() selected=is_selected>{option.clone()}</option>}
+                        }).collect::<Vec<_>>()
+                    }
+                </select>
+                <HpView companion=Some(indx)/>
+            </div>
+            <Show when=move || !collapse.get()>
+                {stats_view.clone()}
+                <div class="flex-row align-center">
+                    <h4>Conditions</h4>
+                    <select on:change=move |event| selected_condition.set(event_target_value(&event))>
+                        <option value="" selected=true>-</option>
+                        {condition_options.iter().map(|name| view! {<option value=name.clone()>{name.clone()}</option>}).collect::<Vec<_>>()}
+                    </select>
+                    <button on:click=add_condition.clone()>Add</button>
+                    {condition_list.clone()}
+                </div>
+            </Show>
+        </div>
+    }
+}
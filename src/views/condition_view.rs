@@ -0,0 +1,97 @@
+use crate::char_data::conditions::{self, Condition};
+use super::view_helpers::get_base_context;
+use leptos::*;
+use leptos::logging::log;
+
+#[component]
+pub fn ConditionView() -> impl IntoView {
+    let (read_char, write_char) = get_base_context("ConditionView");
+    let all_conditions = use_context::<Vec<Condition>>().expect("ConditionView: Expected condition list to be set");
+    let selected_condition = create_rw_signal(String::from(""));
+    let condition_options: Vec<String> = all_conditions.iter().map(|cond| cond.name.clone()).collect();
+    let catalog_for_add = all_conditions.clone();
+    let all_conditions_for_list = all_conditions.clone();
+    let add_selected_condition = move |_| {
+        let name = selected_condition.get_untracked();
+        if name.is_empty() {
+            return;
+        }
+        write_char.update(|c| {
+            if let Err(err) = c.add_condition(&catalog_for_add, &name, None) {
+                log!("ConditionView: {err}");
+            }
+        });
+    };
+    view! {
+        <div class="flex-col">
+            <div class="flex-row align-center">
+                <h4>Conditions</h4>
+                <select name="condition" id="condition_select"
+                    on:change=move |event| selected_condition.set(event_target_value(&event))
+                >
+                    <option value="" selected=true>-</option>
+                    {
+                        condition_options.into_iter().map(|name| view!{
+                            <option value=name.clone()>{name.clone()}</option>
+                        }).collect::<Vec<_>>()
+                    }
+                </select>
+                <button on:click=add_selected_condition>Add</button>
+            </div>
+            <div class="flex-row">
+                <For
+                    each=move || read_char.with(|c| c.conditions.clone())
+                    key=|cond| cond.name.clone()
+                    children=move |cond| {
+                        let collapse = create_rw_signal(false);
+                        let name = cond.name.clone();
+                        let get_value_text = {
+                            let name = cond.name.clone();
+                            move || read_char.with(|c| match c.get_condition_indx_from_name(&name) {
+                                Some(indx) if c.conditions[indx].has_value => c.conditions[indx].get_value().to_string(),
+                                _ => String::from(""),
+                            })
+                        };
+                        let is_implied_only = {
+                            let name = cond.name.clone();
+                            move || read_char.with(|c| match c.get_condition_indx_from_name(&name) {
+                                Some(indx) => !c.conditions[indx].explicit,
+                                None => false,
+                            })
+                        };
+                        let get_explanation = {
+                            let name = cond.name.clone();
+                            move || read_char.with(|c| conditions::explain_condition(&c.conditions, &name).join("\n"))
+                        };
+                        let change_value = {
+                            let name = cond.name.clone();
+                            let catalog = all_conditions_for_list.clone();
+                            move |offset: i32| write_char.update(|c| c.change_condition_value(&catalog, &name, offset))
+                        };
+                        let change_value_right_click = change_value.clone();
+                        view! {
+                            <div class="flex-col bright-bg"
+                                class:implied-condition=is_implied_only
+                                title=get_explanation
+                                on:contextmenu=move |_| change_value_right_click(-1)
+                            >
+                                <div class="flex-row feat-title-row">
+                                    <h4 on:click=move |_| collapse.update(|c| *c = !*c)>{name}</h4>
+                                    <Show when=move || cond.has_value>
+                                        <h4 on:click={let change_value = change_value.clone(); move |_| change_value(1)}>
+                                            {get_value_text.clone()}
+                                        </h4>
+                                    </Show>
+                                </div>
+                                <Show when=move || collapse.get()>
+                                    <hr/>
+                                    <p class="tiny-text" inner_html={let desc = cond.description.clone(); move || desc.clone()}></p>
+                                </Show>
+                            </div>
+                        }
+                    }
+                />
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use crate::char_data::ancestries::AncestryData;
+use crate::char_data::backgrounds::BackgroundData;
+use crate::char_data::classes::ClassData;
+use crate::char_data::creation::{CreationChoices, ATTRIBUTE_IDS};
+use crate::char_data::feats::{Feat, FeatType};
+use crate::char_data::proficiency::ProficiencyLevel;
+use crate::char_data::stat_catalog::StatCatalog;
+use crate::char_data::stats::ProficiencyType;
+use super::view_helpers::{get_base_context, set_slot};
+use leptos::*;
+
+const STEP_NAMES: [&str; 6] = ["Ancestry", "Background", "Class", "Attributes", "Skills", "Feats"];
+
+/// Select with an empty "-" option, calls on_change with the chosen value
+fn choice_select<G, S>(options: Vec<String>, get_value: G, on_change: S) -> impl IntoView
+where
+    G: Fn() -> String + Clone + 'static,
+    S: Fn(String) + 'static,
+{
+    view! {
+        <select on:change=move |event| on_change(event_target_value(&event))>
+            <option value="" selected={let get_value = get_value.clone(); move || get_value().is_empty()}>-</option>
+            {
+                options.into_iter().map(|option| {
+                    let get_value = get_value.clone();
+                    let is_selected = {let option = option.clone(); move || get_value() == option};
+                    view! {
+                        <option value=option.clone() selected=is_selected>{option.clone()}</option>
+                    }
+                }).collect::<Vec<_>>()
+            }
+        </select>
+    }
+}
+
+fn attribute_options() -> Vec<String> {
+    ATTRIBUTE_IDS.iter().map(|id| id.to_string()).collect()
+}
+
+/// Step by step builder shown while the sheet has no character, the finished character is saved like any other change
+#[component]
+pub fn CreationView() -> impl IntoView {
+    let (_, write_ketra) = get_base_context("CreationView");
+    let ancestries = use_context::<Vec<AncestryData>>().expect("CreationView: Expected ancestry list to be set");
+    let backgrounds = use_context::<Vec<BackgroundData>>().expect("CreationView: Expected background list to be set");
+    let classes = use_context::<Vec<ClassData>>().expect("CreationView: Expected class list to be set");
+    let catalog = use_context::<StatCatalog>().expect("CreationView: Expected stat catalog to be set");
+    let feat_map = use_context::<HashMap<String, Feat>>().expect("CreationView: Expected feat map to be set");
+    let choices = create_rw_signal(CreationChoices::default());
+    let step = create_rw_signal(0usize);
+    let error = create_rw_signal(String::from(""));
+
+    let attribute_preview = {
+        let (ancestries, backgrounds, classes) = (ancestries.clone(), backgrounds.clone(), classes.clone());
+        move || choices.with(|c| match c.get_attributes(&ancestries, &backgrounds, &classes) {
+            Ok(attributes) => attributes.as_vec().iter().map(|attribute| format!("{0} {1}", attribute.get_abbr(), attribute.value)).collect::<Vec<_>>().join(" "),
+            Err(err) => err,
+        })
+    };
+
+    let ancestry_step = {
+        let ancestries = ancestries.clone();
+        let ancestry_options: Vec<String> = ancestries.iter().map(|ancestry| ancestry.name.clone()).collect();
+        let get_ancestry = move || choices.with(|c| c.get_ancestry(&ancestries).ok().cloned());
+        let get_ancestry_for_boosts = get_ancestry.clone();
+        view! {
+            <label>Name
+                <input type="text"
+                    prop:value=move || choices.with(|c| c.name.clone())
+                    on:change=move |event| choices.update(|c| c.name = event_target_value(&event))
+                />
+            </label>
+            <label>Ancestry
+                {choice_select(ancestry_options, move || choices.with(|c| c.ancestry.clone()), move |value| choices.update(|c| {
+                    c.ancestry = value;
+                    c.heritage = String::from("");
+                    c.ancestry_boosts = vec![];
+                }))}
+            </label>
+            {move || get_ancestry().map(|ancestry| {
+                let heritage_options: Vec<String> = ancestry.heritages.iter().map(|heritage| heritage.name.clone()).collect();
+                view! {
+                    <p>{format!("HP {0}, {1}, Speed {2}ft., boosts {3:?}, flaws {4:?}", ancestry.hp, ancestry.size, ancestry.speed, ancestry.boosts, ancestry.flaws)}</p>
+                    <label>Heritage
+                        {choice_select(heritage_options, move || choices.with(|c| c.heritage.clone()), move |value| choices.update(|c| c.heritage = value))}
+                    </label>
+                }
+            })}
+            {move || get_ancestry_for_boosts().map(|ancestry| {
+                let free_count = ancestry.boosts.iter().filter(|boost| *boost == "free").count();
+                (0..free_count).map(|indx| view! {
+                    <label>Free ancestry boost
+                        {choice_select(
+                            attribute_options(),
+                            move || choices.with(|c| c.ancestry_boosts.get(indx).cloned().unwrap_or_default()),
+                            move |value| choices.update(|c| set_slot(&mut c.ancestry_boosts, indx, value))
+                        )}
+                    </label>
+                }).collect::<Vec<_>>()
+            })}
+        }
+    };
+
+    let background_step = {
+        let backgrounds = backgrounds.clone();
+        let background_options: Vec<String> = backgrounds.iter().map(|background| background.name.clone()).collect();
+        let get_background = move || choices.with(|c| c.get_background(&backgrounds).ok().cloned());
+        view! {
+            <label>Background
+                {choice_select(background_options, move || choices.with(|c| c.background.clone()), move |value| choices.update(|c| {
+                    c.background = value;
+                    c.background_boosts = vec![];
+                }))}
+            </label>
+            {move || get_background().map(|background| view! {
+                <p>{format!("Trained in {0}, {1}, feat {2}", background.trained_skills.join(", "), background.trained_lore, background.feat)}</p>
+                <label>Background boost
+                    {choice_select(
+                        background.boosts.clone(),
+                        move || choices.with(|c| c.background_boosts.first().cloned().unwrap_or_default()),
+                        move |value| choices.update(|c| set_slot(&mut c.background_boosts, 0, value))
+                    )}
+                </label>
+                <label>Free background boost
+                    {choice_select(
+                        attribute_options(),
+                        move || choices.with(|c| c.background_boosts.get(1).cloned().unwrap_or_default()),
+                        move |value| choices.update(|c| set_slot(&mut c.background_boosts, 1, value))
+                    )}
+                </label>
+            })}
+        }
+    };
+
+    let class_step = {
+        let classes = classes.clone();
+        let class_options: Vec<String> = classes.iter().map(|class_data| class_data.name.clone()).collect();
+        let get_class = move || choices.with(|c| c.get_class(&classes).ok().cloned());
+        view! {
+            <label>Class
+                {choice_select(class_options, move || choices.with(|c| c.class.clone()), move |value| choices.update(|c| {
+                    c.class = value;
+                    c.key_attribute = String::from("");
+                }))}
+            </label>
+            {move || get_class().map(|class_data| view! {
+                <p>{format!("HP {0}, trained in {1} and {2} more skills", class_data.hp, class_data.trained_skills.join(", "), class_data.additional_skills)}</p>
+                <label>Key attribute
+                    {choice_select(
+                        class_data.key_attributes.clone(),
+                        move || choices.with(|c| c.key_attribute.clone()),
+                        move |value| choices.update(|c| c.key_attribute = value)
+                    )}
+                </label>
+            })}
+        }
+    };
+
+    let attribute_step = view! {
+        {
+            (0..4).map(|indx| view! {
+                <label>Free boost
+                    {choice_select(
+                        attribute_options(),
+                        move || choices.with(|c| c.free_boosts.get(indx).cloned().unwrap_or_default()),
+                        move |value| choices.update(|c| set_slot(&mut c.free_boosts, indx, value))
+                    )}
+                </label>
+            }).collect::<Vec<_>>()
+        }
+    };
+
+    let skill_step = {
+        let (ancestries, backgrounds, classes) = (ancestries.clone(), backgrounds.clone(), classes.clone());
+        let skill_names: Vec<String> = catalog.stats
+            .iter()
+            .filter(|stat| stat.p_type == ProficiencyType::Skill && stat.proficiency == ProficiencyLevel::Untrained)
+            .map(|stat| stat.name.clone())
+            .collect();
+        let get_count_text = move || choices.with(|c| match c.get_skill_choice_count(&ancestries, &backgrounds, &classes) {
+            Ok(count) => format!("{0} / {count} additional skills", c.skills.len()),
+            Err(err) => err,
+        });
+        view! {
+            <p>{get_count_text}</p>
+            {
+                skill_names.into_iter().map(|skill| {
+                    let is_checked = {let skill = skill.clone(); move || choices.with(|c| c.skills.contains(&skill))};
+                    let toggle = {let skill = skill.clone(); move |_| choices.update(|c| {
+                        match c.skills.iter().position(|chosen| *chosen == skill) {
+                            Some(indx) => {c.skills.remove(indx);},
+                            None => c.skills.push(skill.clone()),
+                        }
+                    })};
+                    view! {
+                        <label>
+                            <input type="checkbox" prop:checked=is_checked on:change=toggle/>
+                            {skill}
+                        </label>
+                    }
+                }).collect::<Vec<_>>()
+            }
+        }
+    };
+
+    let feat_step = {
+        let feats: Vec<Feat> = feat_map.values().cloned().collect();
+        let get_feat_options = move |feat_type: FeatType, trait_name: String| {
+            let mut options: Vec<String> = feats
+                .iter()
+                .filter(|feat| feat.feat_types.contains(&feat_type) && feat.traits.contains(&trait_name) && feat.level <= 1)
+                .map(|feat| feat.name.clone())
+                .collect();
+            options.sort();
+            options
+        };
+        let get_feat_options_for_class = get_feat_options.clone();
+        view! {
+            {move || {
+                let ancestry = choices.with(|c| c.ancestry.clone());
+                view! {
+                    <label>Ancestry feat
+                        {choice_select(
+                            get_feat_options(FeatType::Ancestry, ancestry),
+                            move || choices.with(|c| c.feats.first().cloned().unwrap_or_default()),
+                            move |value| choices.update(|c| set_slot(&mut c.feats, 0, value))
+                        )}
+                    </label>
+                }
+            }}
+            {move || {
+                let class_name = choices.with(|c| c.class.clone());
+                view! {
+                    <label>Class feat
+                        {choice_select(
+                            get_feat_options_for_class(FeatType::Class, class_name),
+                            move || choices.with(|c| c.feats.get(1).cloned().unwrap_or_default()),
+                            move |value| choices.update(|c| set_slot(&mut c.feats, 1, value))
+                        )}
+                    </label>
+                }
+            }}
+        }
+    };
+
+    let steps = vec![
+        ancestry_step.into_view(),
+        background_step.into_view(),
+        class_step.into_view(),
+        attribute_step.into_view(),
+        skill_step.into_view(),
+        feat_step.into_view(),
+    ];
+    let create_character = move |_| {
+        let result = choices.with_untracked(|c| c.build(&ancestries, &backgrounds, &classes, &catalog, &feat_map));
+        match result {
+            Ok(character) => {
+                error.set(String::from(""));
+                write_ketra.set(character);
+            },
+            Err(err) => error.set(err),
+        }
+    };
+    view! {
+        <div class="flex-col creation-view">
+            <h3>{move || format!("New character: {0}", STEP_NAMES[step.get()])}</h3>
+            <div class="flex-col">
+                {move || steps[step.get()].clone()}
+            </div>
+            <p>{attribute_preview}</p>
+            <div class="flex-row">
+                <button disabled=move || step.get() == 0 on:click=move |_| step.update(|s| *s -= 1)>Back</button>
+                <Show when=move || step.get() + 1 < STEP_NAMES.len()
+                    fallback=move || view! {<button on:click=create_character.clone()>Create</button>}
+                >
+                    <button on:click=move |_| step.update(|s| *s += 1)>Next</button>
+                </Show>
+            </div>
+            <p class="error">{move || error.get()}</p>
+        </div>
+    }
+}
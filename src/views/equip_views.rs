@@ -1,4 +1,9 @@
-use crate::char_data::gear::{Gear, GearType, WeaponType};
+use crate::char_data::conditions::Condition;
+use crate::char_data::gear::{Gear, GearType};
+use crate::char_data::rolls::RollResult;
+use crate::char_data::strikes::{Strike, ATTACKS_PER_TURN};
+use crate::error_template::SheetError;
+use crate::server_side::server_functions::roll_die;
 use crate::char_data::tactics::Tactic;
 use crate::views::action_view::ActionView;
 use crate::views::view_helpers::*;
@@ -11,20 +16,31 @@ pub fn EquipView() -> impl IntoView {
     let (character_data, _) = get_base_context("EquipView");
     view! {
         <For
-            each=move ||character_data.with(|k| k.gear_list.clone())
+            each=move || character_data.with(|k| k.get_unarmed_attacks())
+            key=|attack| attack.name.clone()
+            children=move |attack| view! {<WeaponView item=attack/>}
+        />
+        <For
+            each=move ||character_data.with(|k| k.get_all_gear())
             key=|gear_item| gear_item.name.clone()
             children=move |gear_item| {
                 let item_name = gear_item.name.clone();
                 if gear_item.g_type == GearType::Weapon {
                     return view! {<WeaponView item=gear_item/>}.into_view();
-                } 
+                }
+                if gear_item.g_type == GearType::Armor {
+                    return view! {<ArmorView item=gear_item/>}.into_view();
+                }
                 let collapse = create_rw_signal(false);
                 view! {
                     <div class="flex-col align-flex-start bright-bg" 
                         on:click=move |_| collapse.update(|c| *c = !*c)
                     >
                         <h4 style="margin:unset">{
-                            move || format!("{item_name}")
+                            move || match character_data.with(|c| c.gear_list.iter().find(|item| item.name == item_name).and_then(|item| item.quantity)) {
+                                Some(quantity) => format!("{item_name} ({quantity})"),
+                                None => item_name.clone(),
+                            }
                         }</h4>
                         <Show when=move || collapse.get()>
                             <TraitView trait_names=gear_item.traits.clone()/>
@@ -42,7 +58,9 @@ pub fn EquipView() -> impl IntoView {
 pub fn WeaponView(
     item: Gear
 ) -> impl IntoView {
-    let (character_data, _) = get_base_context("WeaponView");
+    let (character_data, character_write) = get_base_context("WeaponView");
+    let sheet_error = get_sheet_error_context("WeaponView");
+    let last_roll = create_rw_signal(None::<RollResult>);
     let debug_name_clone = item.name.clone();
     let mut err_text = String::from("");
 
@@ -61,8 +79,32 @@ pub fn WeaponView(
         return err_text.into_view();
     }
 
+    let weapon_name = item.name.clone();
+    let roll_strike = create_action(move |attack: &i32| {
+        let attack = *attack;
+        let weapon_name = weapon_name.clone();
+        async move {
+            let breakdown = character_data.with_untracked(|c| c.check_ammunition(&weapon_name).and_then(|_| c.get_strike(&weapon_name)))
+                .map(|strike| strike.get_attack_breakdown(attack));
+            let mut roll = match breakdown {
+                Ok(breakdown) => roll_die(20).await.map(|die| RollResult::new(&weapon_name, die, breakdown)).map_err(|err| err.to_string()),
+                Err(err) => Err(err),
+            };
+            // The ammunition is only spent once the roll went through
+            if roll.is_ok() {
+                let mut result = Ok(());
+                character_write.update(|c| result = c.use_ammunition(&weapon_name));
+                roll = result.and(roll);
+            }
+            match roll {
+                Ok(roll) => last_roll.set(Some(roll)),
+                Err(err) => {log!("WeaponView: {err}"); sheet_error.set(SheetError::new(&err))},
+            }
+        }
+    });
+
     let get_weapon = move || {
-        let weapon_Item = character_data.with(|c| c.gear_list.iter().find(|i| i.name == item.name.clone()).cloned());
+        let weapon_Item = character_data.with(|c| c.get_weapons().into_iter().find(|i| i.name == item.name.clone()));
         match weapon_Item {
             Some(weapon) => {
                 Ok(weapon)
@@ -74,50 +116,65 @@ pub fn WeaponView(
         }
     };
 
-    let get_proficicency = move |weapon: &Gear| {
-        let prof_copy = weapon.proficiency.clone().expect("WeaponView: There should be a weapon proficiency");
-        let stat_opt = character_data.with(|c| c.get_prof_obj_from_name(&prof_copy));
-        match stat_opt {
-            Some(prof) => Ok(prof),
-            None => {let name_copy = prof_copy.clone(); Err(format!("WeaponView: Could not find a proficiency with name {name_copy}"))}
-        }
-    };
-
     let get_weapon_view = move || -> Result<View, String> {
         let weapon = get_weapon()?;
-        let mut stat = get_proficicency(&weapon)?;
-        let weap_info = weapon.weap_info.expect("WeaponView: We expect weapon_data by now. It was checked before");
-        let is_melee = weap_info.w_type == WeaponType::Melee;
-        stat.attribute = if is_melee {"str"} else {"dex"}.to_string();
-        let attack_bonus = character_data.with(|c|stat.calculate_stat(c));
-        let bonus_progression_proficiency = character_data.with(|c|c.abp_data.attack_pot);
-        let prefix = String::from(
-            if attack_bonus + bonus_progression_proficiency > 0 {"+"} else {""}
-        );
-        let dice_amount = character_data.with(|c|c.abp_data.attack_dice);
-        let dice_size = weap_info.damage;
-        let stat_bonus_dmg = if weap_info.w_type == WeaponType::Melee || weapon.traits.iter().any(|t|t=="Propulsive") {
-            let mut val = character_data.with(|c| c.attributes.get_stat_val("str"))?;
-            if weapon.traits.iter().any(|t|t=="Propulsive") {
-                val /= 2;
+        let strike = character_data.with(|c| Strike::from_weapon(&weapon, c))?;
+        let attack_views = (0..ATTACKS_PER_TURN).map(|attack| {
+            let breakdown = strike.get_attack_breakdown(attack);
+            view! {
+                <p title=breakdown.get_tooltip() on:contextmenu=move |event| {event.prevent_default(); roll_strike.dispatch(attack)}>
+                    {format!("{0}{1}", get_prefix(breakdown.total), breakdown.total)}
+                </p>
             }
-            let prefix = get_prefix(val);
-            format!(" {prefix}{val}")
+        }).collect::<Vec<_>>();
+        let range_label = strike.range_increment.map(|_| view! {<div>Range</div>});
+        let range_views = strike.get_range_breakdowns().into_iter().map(|(distance, breakdown)| view! {
+            <div title=breakdown.get_tooltip()>{format!("{distance}ft. {0}{1}", get_prefix(breakdown.total), breakdown.total)}</div>
+        }).collect::<Vec<_>>();
+        let mut ranged_lines = vec![];
+        if let Some(reload) = strike.reload {
+            ranged_lines.push(format!("Reload {reload}"));
+        }
+        if let Some(ammunition) = &strike.ammunition {
+            let left = character_data.with(|c| c.gear_list.iter().find(|ammo| ammo.name == *ammunition).and_then(|ammo| ammo.quantity)).unwrap_or(0);
+            ranged_lines.push(format!("{ammunition}: {left} left"));
         }
-        else {"".to_string()};
-        let dam_type = weap_info.d_type;
-        let full_damage_text = format!("{dice_amount}d{dice_size}{stat_bonus_dmg}{dam_type}");
-        let total_bonus = format!("{0}{1} {2}", prefix, attack_bonus + bonus_progression_proficiency, full_damage_text);
+        let ranged_views = ranged_lines.into_iter().map(|line| view! {<div>{line}</div>}).collect::<Vec<_>>();
+        let rune_text = weapon.weap_info.as_ref().map(|info| {
+            let mut runes = vec![];
+            if info.runes.potency > 0 {
+                runes.push(format!("+{0}", info.runes.potency));
+            }
+            runes.extend(["Striking", "Greater Striking", "Major Striking"].get((info.runes.striking - 1) as usize).map(|name| name.to_string()));
+            runes.extend(info.runes.property.iter().map(|rune| rune.name.clone()));
+            runes.join(", ")
+        }).unwrap_or_default();
+        let rune_tooltip = weapon.weap_info.as_ref().map(|info| {
+            info.runes.property.iter().map(|rune| format!("{0}: {1}", rune.name, rune.description)).collect::<Vec<_>>().join("\n")
+        }).unwrap_or_default();
+        let profile_views = strike.profiles.iter().map(|profile| view! {
+            <div class="flex-col" title=profile.bonus.get_tooltip()>
+                <div>{format!("{0}: {1}", profile.name, profile.get_text())}</div>
+                <div>{format!("Critical: {0}", strike.get_critical_text(profile))}</div>
+            </div>
+        }).collect::<Vec<_>>();
+        let critical_specialization = strike.critical_specialization.clone().map(|effect| view! {
+            <div class="tiny-text"><b>Critical specialization </b>{effect}</div>
+        });
         Ok(view!{
             <div class="flex-col bright-bg">
                 <div class="flex-row">
-                    <h4>{let name_clone = weapon.name.clone(); move|| name_clone.clone()}</h4>
-                    <p>{
-                        move || total_bonus.clone()
-                    }</p>
+                    <h4>{strike.name.clone()}</h4>
+                    {attack_views}
                     <p inner_html={move|| weapon.description.clone()}/>
                 </div>
-                <TraitView trait_names=weapon.traits.clone()/>
+                <div class="flex-row tiny-text">{profile_views}</div>
+                <div class="flex-row tiny-text">{range_label}{range_views}{ranged_views}</div>
+                {critical_specialization}
+                <Show when={let rune_text = rune_text.clone(); move || !rune_text.is_empty()}>
+                    <div class="tiny-text" title=rune_tooltip.clone()>{rune_text.clone()}</div>
+                </Show>
+                <TraitView trait_names=strike.traits.clone()/>
             </div>
         }.into_view())
     };
@@ -131,13 +188,84 @@ pub fn WeaponView(
                 }.into_view()
             }
         }}    
+        {move || last_roll.get().map(|roll| view! {
+            <p class="tiny-text" title=roll.breakdown.get_tooltip()>{roll.get_text()}</p>
+        })}
     }.into_view()
 }
 
+#[component]
+pub fn ArmorView(
+    item: Gear
+) -> impl IntoView {
+    let (character_data, character_write) = get_base_context("ArmorView");
+    let conditions = use_context::<Vec<Condition>>().expect("ArmorView: Expected condition list to be set");
+    let collapse = create_rw_signal(false);
+    let armor_name = item.name.clone();
+    let armor_text = match &item.armor_info {
+        Some(info) => {
+            let dex_cap_text = match info.dex_cap {
+                Some(cap) => format!(", Dex cap {cap}"),
+                None => String::from(""),
+            };
+            let strength_text = match info.strength {
+                Some(strength) => format!(", Str {strength}"),
+                None => String::from(""),
+            };
+            let potency_text = if info.runes.potency > 0 {format!(" +{0}", info.runes.potency)} else {String::from("")};
+            format!(
+                "{0:?}{potency_text} AC +{1}{dex_cap_text}, Check -{2}, Speed -{3}ft.{strength_text}",
+                info.category, info.ac_bonus, info.check_penalty, info.speed_penalty
+            )
+        },
+        None => {
+            log!("ArmorView: This item does not have Armor Info: {armor_name}");
+            String::from("No armor info")
+        },
+    };
+    let is_worn = {
+        let armor_name = item.name.clone();
+        move || character_data.with(|c| c.gear_list.iter().any(|i| i.name == armor_name && i.worn))
+    };
+    let toggle_worn = {
+        let armor_name = item.name.clone();
+        let is_worn = is_worn.clone();
+        move |_| {
+            let new_worn = if is_worn() {String::from("")} else {armor_name.clone()};
+            let mut result = Ok(());
+            character_write.update(|c| {
+                c.set_worn_armor(&new_worn);
+                result = c.sync_granted_conditions(&conditions);
+            });
+            if let Err(err) = result {
+                log!("ArmorView: {err}");
+            }
+        }
+    };
+    view! {
+        <div class="flex-col align-flex-start bright-bg"
+            class:worn-armor=is_worn
+            on:click=move |_| collapse.update(|c| *c = !*c)
+            on:contextmenu=toggle_worn
+        >
+            <div class="flex-row feat-title-row">
+                <h4>{armor_name}</h4>
+                <p class="tiny-text">{armor_text}</p>
+            </div>
+            <Show when=move || collapse.get()>
+                <TraitView trait_names=item.traits.clone()/>
+                <hr/>
+                <div class="tiny-text" inner_html={let desc = item.description.clone(); move || desc.clone()}></div>
+            </Show>
+        </div>
+    }
+}
+
 #[component]
 pub fn TacticsView() -> impl IntoView {
     let (character_data, character_write) = get_base_context("TacticsView");
-    let max_tactics = 2;
+    let conditions = use_context::<Vec<Condition>>().expect("TacticsView: Expected condition list to be set");
+    let max_tactics = move || character_data.with(|c| c.get_max_tactics());
     let count_tactics = {
         move || character_data.with(|c| {
             c.tactics.iter().filter(|tactic| tactic.selected).count()
@@ -145,6 +273,7 @@ pub fn TacticsView() -> impl IntoView {
     };
     let tactics_header = move || {
         let count = count_tactics();
+        let max_tactics = max_tactics();
         format!("Tactics [{count} / {max_tactics}]")
     };
     view! {
@@ -166,14 +295,20 @@ pub fn TacticsView() -> impl IntoView {
                         let update_selected_on_tactic = {
                             let tac_name2 = tactic.name.clone();
                             let get_selected_on_tactic_clone = get_selected_on_tactic.clone();
+                            let conditions = conditions.clone();
                             move |_| {
-                                if count_tactics() >= max_tactics && !get_selected_on_tactic_clone() {
+                                if count_tactics() >= max_tactics() as usize && !get_selected_on_tactic_clone() {
                                     return;
                                 }
+                                let mut result = Ok(());
                                 character_write.update(|c|{
                                     let mut_ref: &mut Tactic = c.tactics.iter_mut().find(|val| val.name == tac_name2).expect("TacticView: update_selected_on_tactic - There should be a tactic of the same name"); 
-                                    mut_ref.selected = !mut_ref.selected
+                                    mut_ref.selected = !mut_ref.selected;
+                                    result = c.sync_granted_conditions(&conditions);
                                 });
+                                if let Err(err) = result {
+                                    log!("TacticsView: {err}");
+                                }
                             }
                         };
                         view! {
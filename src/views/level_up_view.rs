@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use crate::char_data::classes::ClassData;
+use crate::char_data::companion::CompanionCatalog;
+use crate::char_data::creation::ATTRIBUTE_IDS;
+use crate::char_data::feats::Feat;
+use crate::char_data::leveling::{has_attribute_boosts, LevelUpChoices, BOOSTS_PER_BOOST_LEVEL};
+use crate::char_data::stat_catalog::StatCatalog;
+use crate::char_data::stats::ProficiencyType;
+use crate::error_template::SheetError;
+use super::view_helpers::{get_base_context, get_sheet_error_context, set_slot};
+use leptos::*;
+use leptos::logging::log;
+
+fn slot_select(options: Vec<String>, on_change: impl Fn(String) + 'static) -> impl IntoView {
+    view! {
+        <select on:change=move |event| on_change(event_target_value(&event))>
+            <option value="" selected=true>-</option>
+            {
+                options.into_iter().map(|option| view! {
+                    <option value=option.clone()>{option.clone()}</option>
+                }).collect::<Vec<_>>()
+            }
+        </select>
+    }
+}
+
+/// Asks for the boosts, skill increase and feats of the next level, then levels the character up
+#[component]
+pub fn LevelUpView(show: RwSignal<bool>) -> impl IntoView {
+    let (read_ketra, write_ketra) = get_base_context("LevelUpView");
+    let sheet_error = get_sheet_error_context("LevelUpView");
+    let classes = use_context::<Vec<ClassData>>().expect("LevelUpView: Expected class list to be set");
+    let catalog = use_context::<StatCatalog>().expect("LevelUpView: Expected stat catalog to be set");
+    let feat_map = use_context::<HashMap<String, Feat>>().expect("LevelUpView: Expected feat map to be set");
+    let companions = use_context::<CompanionCatalog>().expect("LevelUpView: Expected companion catalog to be set");
+    let choices = create_rw_signal(LevelUpChoices::default());
+    let class_data = read_ketra.with_untracked(|c| c.get_class_data(&classes).ok().cloned());
+    let class_data = match class_data {
+        Some(class_data) => class_data,
+        None => return view! {<p class="error">Choose a class before leveling up</p>}.into_view(),
+    };
+    let new_level = read_ketra.with_untracked(|c| c.level + 1);
+
+    let boost_view = has_attribute_boosts(new_level).then(|| view! {
+        <h4>Attribute boosts</h4>
+        <div class="flex-row">
+            {
+                (0..BOOSTS_PER_BOOST_LEVEL).map(|indx| slot_select(
+                    ATTRIBUTE_IDS.iter().map(|id| id.to_string()).collect(),
+                    move |value| choices.update(|c| set_slot(&mut c.boosts, indx, value))
+                )).collect::<Vec<_>>()
+            }
+        </div>
+    });
+
+    let skill_view = class_data.has_skill_increase(new_level).then(|| {
+        let skill_options: Vec<String> = read_ketra.with_untracked(|c| c.proficiencies
+            .iter()
+            .filter(|prof| prof.p_type == ProficiencyType::Skill || prof.p_type == ProficiencyType::Lore)
+            .filter(|prof| prof.proficiency.next().is_some_and(|next| next.get_min_level() <= new_level))
+            .map(|prof| prof.name.clone())
+            .collect());
+        view! {
+            <h4>Skill increase</h4>
+            {slot_select(skill_options, move |value| choices.update(|c| c.skill_increase = value))}
+        }
+    });
+
+    let feat_view = class_data.get_feat_slots(new_level).into_iter().enumerate().map(|(indx, feat_type)| {
+        let mut feat_options: Vec<String> = feat_map
+            .values()
+            .filter(|feat| feat.feat_types.contains(&feat_type) && feat.level <= new_level)
+            .map(|feat| feat.name.clone())
+            .collect();
+        feat_options.sort();
+        view! {
+            <h4>{format!("{feat_type:?} feat")}</h4>
+            {slot_select(feat_options, move |value| choices.update(|c| set_slot(&mut c.feats, indx, value)))}
+        }
+    }).collect::<Vec<_>>();
+
+    let features = class_data.get_features_at_level(new_level).into_iter().map(|feature| view! {
+        <p title=feature.description.clone()>{feature.name.clone()}</p>
+    }).collect::<Vec<_>>();
+
+    let confirm_level_up = move |_| {
+        let chosen = choices.get_untracked();
+        let mut result = Ok(());
+        write_ketra.update(|c| result = c.level_up(&chosen, &classes, &feat_map, &catalog)
+            .and_then(|_| c.sync_feat_additions(&feat_map, &catalog))
+            .and_then(|_| c.sync_companions(&companions)));
+        match result {
+            Ok(_) => {
+                sheet_error.set(SheetError::new(""));
+                show.set(false);
+            },
+            Err(err) => {log!("LevelUpView: {err}"); sheet_error.set(SheetError::new(&err))},
+        }
+    };
+    view! {
+        <div class="flex-col level-up-popup">
+            <h3>{format!("Level {new_level}")}</h3>
+            {boost_view}
+            {skill_view}
+            {feat_view}
+            <Show when={let has_features = !features.is_empty(); move || has_features}>
+                <h4>Class features</h4>
+            </Show>
+            {features}
+            <div class="flex-row">
+                <button on:click=move |_| show.set(false)>Cancel</button>
+                <button on:click=confirm_level_up>Level up</button>
+            </div>
+        </div>
+    }.into_view()
+}
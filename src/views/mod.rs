@@ -2,4 +2,10 @@ pub mod stats_views;
 pub mod character_view;
 pub mod equip_views;
 pub mod action_view;
-pub mod view_helpers;
\ No newline at end of file
+pub mod view_helpers;
+pub mod condition_view;
+pub mod creation_view;
+pub mod level_up_view;
+pub mod spell_view;
+pub mod companion_view;
+
@@ -0,0 +1,188 @@
+use crate::char_data::modifiers::StatBreakdown;
+use crate::char_data::spells::{CastingType, Spell, Spellcasting};
+use crate::error_template::SheetError;
+use super::action_view::ActionView;
+use super::stats_views::{BreakdownView, TraitView};
+use super::view_helpers::{get_base_context, get_sheet_error_context};
+use leptos::*;
+use leptos::logging::log;
+
+/// Spell attack, spell DC, slots and known spells of a spellcaster. Shows nothing for characters without spellcasting
+#[component]
+pub fn SpellView() -> impl IntoView {
+    let (read_character, write_character) = get_base_context("SpellView");
+    let sheet_error = get_sheet_error_context("SpellView");
+    let spells = use_context::<Vec<Spell>>().expect("SpellView: Expected spell list to be set");
+    let selected_spell = create_rw_signal(String::from(""));
+
+    let change_spellcasting = move |change: &dyn Fn(&mut Spellcasting) -> Result<(), String>| {
+        let mut result = Ok(());
+        write_character.update(|c| result = c.get_spellcasting_mut().and_then(change));
+        match result {
+            Ok(_) => sheet_error.set(SheetError::new("")),
+            Err(err) => {log!("SpellView: {err}"); sheet_error.set(SheetError::new(&err))},
+        }
+    };
+    let get_breakdown = move |dc: bool| Signal::derive(move || read_character.with(|c| {
+        let breakdown = if dc {c.calculate_spell_dc_breakdown()} else {c.calculate_spell_attack_breakdown()};
+        breakdown.unwrap_or_else(|err| {log!("SpellView: {err}"); StatBreakdown::new(0, vec![])})
+    }));
+
+    let slot_view = move || read_character.with(|c| c.spellcasting.as_ref().map(|spellcasting| {
+        let is_spontaneous = spellcasting.casting_type == CastingType::Spontaneous;
+        spellcasting.slots.iter().map(|slot| {
+            let rank = slot.rank;
+            let slot_text = if is_spontaneous {
+                format!("Rank {rank}: {0} / {1}", slot.max - slot.used, slot.max)
+            }
+            else {
+                format!("Rank {rank}: {0} slots", slot.max)
+            };
+            view! {
+                <div class="bright-bg"
+                    on:click=move |_| if is_spontaneous {change_spellcasting(&|s| s.expend_slot(rank))}
+                    on:contextmenu=move |_| if is_spontaneous {change_spellcasting(&|s| s.restore_slot(rank))}
+                >{slot_text}</div>
+            }
+        }).collect::<Vec<_>>()
+    }));
+
+    let prepared_view = {
+        let spells = spells.clone();
+        move || read_character.with(|c| c.spellcasting.as_ref().map(|spellcasting| {
+            spellcasting.prepared.iter().enumerate().map(|(indx, prepared)| {
+                let rank = prepared.rank;
+                let spell_name = prepared.spell.clone();
+                let cast_spells = spells.clone();
+                let cast_prepared = move |_| match cast_spells.iter().find(|spell| spell.name == spell_name) {
+                    Some(spell) => change_spellcasting(&|s| s.cast_spell(spell, rank)),
+                    None => log!("SpellView: Could not find the spell {spell_name}"),
+                };
+                view! {
+                    <div class="bright-bg" class:expended-spell=prepared.expended
+                        on:click=cast_prepared
+                        on:contextmenu=move |_| change_spellcasting(&|s| {s.unprepare_spell(indx); Ok(())})
+                    >{format!("{0} ({rank})", prepared.spell)}</div>
+                }
+            }).collect::<Vec<_>>()
+        }))
+    };
+
+    let known_view = {
+        let spells = spells.clone();
+        move || read_character.with(|c| c.spellcasting.as_ref().map(|spellcasting| {
+            let mut known: Vec<Spell> = spells.iter().filter(|spell| spellcasting.known.contains(&spell.name)).cloned().collect();
+            known.sort_by_key(|spell| (spell.rank, spell.name.clone()));
+            let max_rank = spellcasting.slots.iter().map(|slot| slot.rank).max().unwrap_or(0);
+            let cantrip_rank = c.get_cantrip_rank();
+            let is_spontaneous = spellcasting.casting_type == CastingType::Spontaneous;
+            known.into_iter().map(|spell| {
+                let collapse = create_rw_signal(false);
+                let cast_rank = create_rw_signal(if spell.is_cantrip() {cantrip_rank} else {spell.rank});
+                let rank_options: Vec<i32> = (spell.get_min_rank()..=max_rank).collect();
+                let heightened_view = {
+                    let spell = spell.clone();
+                    move || spell.get_heightened_text(cast_rank.get()).into_iter().map(|line| view! {<div class="tiny-text">{line}</div>}).collect::<Vec<_>>()
+                };
+                let use_spell = {
+                    let spell = spell.clone();
+                    move || {
+                        let rank = cast_rank.get_untracked();
+                        if is_spontaneous || spell.is_cantrip() {
+                            change_spellcasting(&|s| s.cast_spell(&spell, rank));
+                        }
+                        else {
+                            change_spellcasting(&|s| s.prepare_spell(&spell, rank));
+                        }
+                    }
+                };
+                let forget_spell = {
+                    let spell_name = spell.name.clone();
+                    move |_| change_spellcasting(&|s| {s.forget_spell(&spell_name); Ok(())})
+                };
+                let button_text = if is_spontaneous || spell.is_cantrip() {"Cast"} else {"Prepare"};
+                let actions = spell.actions;
+                let traits = spell.traits.clone();
+                let description = spell.description.clone();
+                view! {
+                    <div class="flex-col align-flex-start bright-bg" on:click=move |_| collapse.update(|c| *c = !*c) on:contextmenu=forget_spell>
+                        <div class="flex-row feat-title-row">
+                            <h4>{spell.name.clone()}</h4>
+                            <Show when=move || actions != 0>
+                                <ActionView number=actions/>
+                            </Show>
+                            <select on:click=|event| event.stop_propagation()
+                                on:change=move |event| cast_rank.set(event_target_value(&event).parse().unwrap_or(1))
+                            >
+                                {
+                                    rank_options.into_iter().map(|rank| view! {
+                                        <option value=rank selected=move || cast_rank.get() == rank>{rank}</option>
+                                    }).collect::<Vec<_>>()
+                                }
+                            </select>
+                            <button on:click=move |event| {event.stop_propagation(); use_spell()}>{button_text}</button>
+                        </div>
+                        <Show when=move || collapse.get()>
+                            <TraitView trait_names=traits.clone()/>
+                            <hr/>
+                            <div class="tiny-text" inner_html=description.clone()></div>
+                            {heightened_view.clone()}
+                        </Show>
+                    </div>
+                }
+            }).collect::<Vec<_>>()
+        }))
+    };
+
+    let rest = {
+        let spells = spells.clone();
+        move |_| write_character.update(|c| c.rest(&spells))
+    };
+
+    let learn_options = {
+        let spells = spells.clone();
+        move || read_character.with(|c| c.spellcasting.as_ref().map(|spellcasting| {
+            let mut options: Vec<String> = spells
+                .iter()
+                .filter(|spell| spell.traditions.contains(&spellcasting.tradition) && !spellcasting.known.contains(&spell.name))
+                .map(|spell| spell.name.clone())
+                .collect();
+            options.sort();
+            options.into_iter().map(|option| view! {<option value=option.clone()>{option.clone()}</option>}).collect::<Vec<_>>()
+        }))
+    };
+    let learn_selected_spell = {
+        let spells = spells.clone();
+        move |_| {
+            let spell_name = selected_spell.get_untracked();
+            if let Some(spell) = spells.iter().find(|spell| spell.name == spell_name) {
+                change_spellcasting(&|s| s.learn_spell(spell));
+            }
+        }
+    };
+
+    view! {
+        <Show when=move || read_character.with(|c| c.spellcasting.is_some())>
+            <div class="flex-col spell-view">
+                <div class="flex-row align-center">
+                    <h4>{move || read_character.with(|c| c.spellcasting.as_ref().map(|s| format!("{0} spells", s.tradition)))}</h4>
+                    <div>Attack</div>
+                    <BreakdownView breakdown=get_breakdown(false)/>
+                    <div>DC</div>
+                    <BreakdownView breakdown=get_breakdown(true)/>
+                    <button on:click=rest.clone()>Rest</button>
+                </div>
+                <div class="flex-row">{slot_view}</div>
+                <div class="flex-row flex-wrap">{prepared_view.clone()}</div>
+                <div class="flex-col">{known_view.clone()}</div>
+                <div class="flex-row align-center">
+                    <select on:change=move |event| selected_spell.set(event_target_value(&event))>
+                        <option value="" selected=true>-</option>
+                        {learn_options.clone()}
+                    </select>
+                    <button on:click=learn_selected_spell.clone()>Learn</button>
+                </div>
+            </div>
+        </Show>
+    }
+}
@@ -1,10 +1,19 @@
 use std::collections::HashMap;
 
+use crate::char_data::conditions::Condition;
 use crate::char_data::feats::Feat;
+use crate::char_data::hp::HpInfo;
+use crate::char_data::modifiers::StatBreakdown;
+use crate::char_data::rolls::RollResult;
+use crate::char_data::spells::Spell;
 use crate::char_data::proficiency::ProficiencyLevel;
 use crate::char_data::stats::ProficiencyType;
+use crate::char_data::stat_catalog::StatCatalog;
+use crate::char_data::traits::Trait;
+use crate::error_template::SheetError;
+use crate::server_side::server_functions::roll_die;
 use super::action_view::ActionView;
-use super::view_helpers::get_base_context;
+use super::view_helpers::{get_base_context, get_sheet_error_context};
 use leptos::ev::Event;
 use leptos::*;
 use leptos::logging::log;
@@ -72,35 +81,29 @@ pub fn MainStatsView() -> impl IntoView {
 }
 
 
+/// Hit points of the character, or of the companion at the given index
 #[component]
 pub fn HpView(
-    horse: bool
+    companion: Option<usize>
 ) -> impl IntoView {
     let (read_char, write_char) = get_base_context("HpView");
     let reset_input = create_rw_signal(false);
     let temp_hp_switch = create_rw_signal(false);
-    let get_hp_info = move || read_char.with(|c| {
-        if horse {
-            c.horse_hp_info.clone()
-        } 
-        else {
-            c.hp_info.clone()
+    let sheet_error = get_sheet_error_context("HpView");
+    let get_hp_info = move || read_char.with(|c| c.get_hp_info(companion).ok().cloned());
+    // A stale companion index changes nothing instead of falling back to the character
+    let update_hp_info = move |change: &dyn Fn(&mut HpInfo)| {
+        let mut result = Ok(());
+        write_char.update(|c| result = c.get_hp_info_mut(companion).map(change));
+        if let Err(err) = result {
+            log!("HpView: {err}");
+            sheet_error.set(SheetError::new(&err));
         }
-    });
-    let change_hp = move |val: i32| {
-        write_char.update(|c| {
-            if horse {
-                c.horse_hp_info.change_hp(val)
-            }
-            else {
-                c.hp_info.change_hp(val)
-            }
-        });
-    }; 
-    let hp_view = move || {
-        let hp = get_hp_info().get_hp();
-        let maxhp = get_hp_info().get_max_hp();
-        format!("{hp}/{maxhp}")
+    };
+    let change_hp = move |val: i32| update_hp_info(&|hp_info| hp_info.change_hp(val));
+    let hp_view = move || match get_hp_info() {
+        Some(hp_info) => format!("{0}/{1}", hp_info.get_hp(), hp_info.get_max_hp()),
+        None => String::from("-"),
     };
     let flip_temp_switch = {
         move || temp_hp_switch.update(|active| *active = !*active)
@@ -125,15 +128,10 @@ pub fn HpView(
                                 prop:value="" 
                                 on:contextmenu=move |_| flip_temp_switch()
                                 on:change=move |event: Event| {
-                                    write_char.update(|c|{
-                                        match event_target_value(&event).parse::<i32>() {
-                                            Ok(number) => {
-                                                if horse {c.horse_hp_info.set_temp(number)}
-                                                else {c.hp_info.set_temp(number)}
-                                            },
-                                            Err(err) => {log!("HpView/tempHP error getting target value: {err}")},
-                                        }
-                                    });
+                                    match event_target_value(&event).parse::<i32>() {
+                                        Ok(number) => update_hp_info(&|hp_info| hp_info.set_temp(number)),
+                                        Err(err) => {log!("HpView/tempHP error getting target value: {err}")},
+                                    }
                                     temp_hp_switch.update(|active| *active = !*active);
                                 }
                             />
@@ -144,7 +142,7 @@ pub fn HpView(
                             <label style="color: blue" name="temphp" id="temphp"
                                 on:contextmenu=move |_| flip_temp_switch()
                             >
-                                {move || get_hp_info().get_temp()}
+                                {move || get_hp_info().map(|hp_info| hp_info.get_temp())}
                             </label>
                         }.into_view()
                     }
@@ -154,7 +152,7 @@ pub fn HpView(
                 type="number" 
                 id="hp_inp" 
                 class="hp-input"
-                placeholder={move || if horse {"Horse"}else{"HP Change"}}
+                placeholder={move || if companion.is_some() {"Companion"}else{"HP Change"}}
                 prop:value=move || {let _ = reset_input.get(); return String::from("")} 
                 on:change=move |event: Event|{ 
                     match event_target_value(&event).parse::<i32>() {
@@ -310,9 +308,9 @@ pub fn ProficiencyListView(
                         let data = get_skill_data.clone();
                         move || data().proficiency.to_string()[..1].to_string()
                     };
-                    let get_skill_val = {
+                    let get_skill_breakdown = {
                         let data = get_skill_data.clone();
-                        move || character_data.with(|c| data().calculate_stat(c))
+                        Signal::derive(move || character_data.with(|c| data().calculate_stat_breakdown(c).unwrap_or_else(|err| {log!("{err}"); StatBreakdown::new(-99, vec![])})))
                     };
                     let is_proficient = {
                         let get_prof = get_skill_prof.clone();
@@ -321,7 +319,7 @@ pub fn ProficiencyListView(
                     view! {
                         <div>{move || name_clone.clone()}</div>
                         <div class="proficiency-letter" class:proficiency-letter-trained=is_proficient>{get_skill_prof}</div>
-                        <div>{get_skill_val}</div>
+                        <BreakdownView breakdown=get_skill_breakdown/>
                     }.into_view()
                 }
             />
@@ -329,6 +327,68 @@ pub fn ProficiencyListView(
     }
 }
 
+#[component]
+pub fn LoreEditView() -> impl IntoView {
+    let (read_char, write_char) = get_base_context("LoreEditView");
+    let sheet_error = get_sheet_error_context("LoreEditView");
+    let catalog = use_context::<StatCatalog>().expect("LoreEditView: Expected stat catalog to be set");
+    let new_lore_name = create_rw_signal(String::from(""));
+    let report = move |result: Result<(), String>| match result {
+        Ok(_) => sheet_error.set(SheetError::new("")),
+        Err(err) => {log!("LoreEditView: {err}"); sheet_error.set(SheetError::new(&err))},
+    };
+    let add_lore = move |_| {
+        let name = new_lore_name.get_untracked();
+        let mut result: Result<(), String> = Ok(());
+        write_char.update(|c| result = c.add_lore(&name, ProficiencyLevel::Trained, &catalog));
+        report(result);
+        new_lore_name.set(String::from(""));
+    };
+    view! {
+        <div class="flex-col">
+            <For
+                each=move || read_char.with(|c| {
+                    c.proficiencies
+                    .iter()
+                    .filter(|prof| prof.p_type == ProficiencyType::Lore)
+                    .map(|prof| prof.name.clone())
+                    .collect::<Vec<String>>()
+                })
+                key=|lore_name| lore_name.clone()
+                children=move |lore_name| {
+                    let rename_from = lore_name.clone();
+                    let remove_name = lore_name.clone();
+                    view! {
+                        <div class="flex-row">
+                            <input type="text" class="lore-input"
+                                prop:value=lore_name.clone()
+                                on:change=move |event| {
+                                    let val: String = event_target_value(&event);
+                                    let mut result: Result<(), String> = Ok(());
+                                    write_char.update(|c| result = c.rename_lore(&rename_from, &val));
+                                    report(result);
+                                }
+                            />
+                            <button on:click=move |_| {
+                                let mut result: Result<(), String> = Ok(());
+                                write_char.update(|c| result = c.remove_lore(&remove_name));
+                                report(result);
+                            }>x</button>
+                        </div>
+                    }
+                }
+            />
+            <div class="flex-row">
+                <input type="text" class="lore-input" placeholder="New Lore"
+                    prop:value=move || new_lore_name.get()
+                    on:input=move |event| new_lore_name.set(event_target_value(&event))
+                />
+                <button on:click=add_lore>Add</button>
+            </div>
+        </div>
+    }
+}
+
 #[component]
 pub fn SwitchProfView(
     types: Vec<ProficiencyType>,
@@ -356,13 +416,13 @@ pub fn DefenseView() -> impl IntoView {
     let (read_character, write_character) = get_base_context("DefenseView");
 
     let shield_raised = move || read_character.with(|c| c.shield_info.raised);
-    let calc_ac = move || read_character.with(|c| c.calculate_ac());
+    let ac_breakdown = Signal::derive(move || read_character.with(|c| c.calculate_ac_breakdown()));
     let switch_shield_pos = move |_| write_character.update(|c| c.shield_info.raised=!c.shield_info.raised);
 
     view!{
         <div class="flex-col" style="align-items: stretch">
             <h3 style="margin: 0; white-spacce:nowrap" on:click=switch_shield_pos class:boosted-stat=shield_raised.clone()>
-                AC: {calc_ac}
+                AC: <BreakdownView breakdown=ac_breakdown/>
             </h3>
             <button on:click=switch_shield_pos style="justify-content:center">
                 {
@@ -379,10 +439,116 @@ pub fn DefenseView() -> impl IntoView {
 }
 
 
+/// Initiative with the stat it is rolled with, right click rolls it
+#[component]
+pub fn InitiativeView() -> impl IntoView {
+    let (read_character, write_character) = get_base_context("InitiativeView");
+    let sheet_error = get_sheet_error_context("InitiativeView");
+    let last_roll = create_rw_signal(None::<RollResult>);
+    let stat_options = create_memo(move |_| read_character.with(|c| c.proficiencies
+        .iter()
+        .filter(|prof| prof.p_type == ProficiencyType::Perception || prof.p_type == ProficiencyType::Skill || prof.p_type == ProficiencyType::Lore)
+        .map(|prof| prof.name.clone())
+        .collect::<Vec<String>>()));
+    let breakdown = Signal::derive(move || read_character.with(|c| match c.calculate_initiative_breakdown() {
+        Ok(breakdown) => breakdown,
+        Err(err) => {log!("InitiativeView: {err}"); StatBreakdown::new(0, vec![])},
+    }));
+    let roll_initiative = create_action(move |_: &i32| async move {
+        match roll_die(20).await {
+            Ok(die) => last_roll.set(Some(RollResult::new("Initiative", die, breakdown.get_untracked()))),
+            Err(err) => {log!("InitiativeView: {err}"); sheet_error.set(SheetError::new(&err.to_string()))},
+        }
+    });
+    let change_skill = move |event: Event| {
+        let skill_name = event_target_value(&event);
+        let mut result = Ok(());
+        write_character.update(|c| result = c.set_initiative_skill(&skill_name));
+        if let Err(err) = result {
+            log!("InitiativeView: {err}");
+            sheet_error.set(SheetError::new(&err));
+        }
+    };
+    view! {
+        <div class="skill-grid" on:contextmenu=move |event| {event.prevent_default(); roll_initiative.dispatch(0)}>
+            <div style="display:flex; flex: 1 0 0">initiative</div>
+            <select class="tiny-text" on:change=change_skill>
+                {move ||
+                    stat_options.get().into_iter().map(|option| {
+                        let is_selected = {
+                            let option = option.clone();
+                            move || read_character.with(|c| c.get_initiative_stat().is_ok_and(|stat| stat.name == option))
+                        };
+                        view! {<option value=option.clone() selected=is_selected>{option.clone()}</option>}
+                    }).collect::<Vec<_>>()
+                }
+            </select>
+            <BreakdownView breakdown=breakdown/>
+        </div>
+        {move || last_roll.get().map(|roll| view! {
+            <p class="tiny-text" title=roll.breakdown.get_tooltip()>{roll.get_text()}</p>
+        })}
+    }
+}
+
+/// Shows the total of a stat, hovering shows the itemized breakdown, tapping toggles it below the value
+#[component]
+pub fn BreakdownView(
+    breakdown: Signal<StatBreakdown>
+) -> impl IntoView {
+    let show_breakdown = create_rw_signal(false);
+    view!{
+        <div class="breakdown-anchor"
+            title=move || breakdown.with(|b| b.get_tooltip())
+            on:click=move |event| {
+                event.stop_propagation();
+                show_breakdown.update(|s| *s = !*s);
+            }
+        >
+            {move || breakdown.with(|b| b.total)}
+            <Show when=move || show_breakdown.get()>
+                <div class="breakdown-popup tiny-text">
+                    {move || breakdown.with(|b| b.get_lines()).into_iter().map(|line| view!{<div>{line}</div>}).collect::<Vec<_>>()}
+                </div>
+            </Show>
+        </div>
+    }
+}
+
 #[component]
 pub fn FeatView() -> impl IntoView {
     let full_feat_map = use_context::<HashMap<String, Feat>>().expect("FeatView: Expected full feat list to be set");
-    let (read_character, _) = get_base_context("FeatView");
+    let catalog = use_context::<StatCatalog>().expect("FeatView: Expected stat catalog to be set");
+    let conditions = use_context::<Vec<Condition>>().expect("FeatView: Expected condition list to be set");
+    let (read_character, write_character) = get_base_context("FeatView");
+    let sheet_error = get_sheet_error_context("FeatView");
+    let selected_feat = create_rw_signal(String::from(""));
+    let mut feat_options: Vec<String> = full_feat_map.keys().cloned().collect();
+    feat_options.sort();
+    let change_feats = {
+        let feat_map = full_feat_map.clone();
+        move |feat_name: String, add: bool| {
+            let mut result = Ok(());
+            write_character.update(|c| result = if add {
+                c.add_feat(&feat_name, &feat_map, &catalog)
+            } else {
+                c.remove_feat(&feat_name, &feat_map, &catalog)
+            }.and_then(|_| c.sync_granted_conditions(&conditions)));
+            match result {
+                Ok(_) => sheet_error.set(SheetError::new("")),
+                Err(err) => {log!("FeatView: {err}"); sheet_error.set(SheetError::new(&err))},
+            }
+        }
+    };
+    let add_selected_feat = {
+        let change_feats = change_feats.clone();
+        move |_| {
+            let feat_name = selected_feat.get_untracked();
+            if !feat_name.is_empty() {
+                change_feats(feat_name, true);
+            }
+        }
+    };
     
     let get_feat_list = move || {
         let mut ret_list = vec![];
@@ -399,12 +565,41 @@ pub fn FeatView() -> impl IntoView {
     };
     view!{
         <div class="flex-col">
-            <h4>Feats</h4>
+            <div class="flex-row align-center">
+                <h4>Feats</h4>
+                <select on:change=move |event| selected_feat.set(event_target_value(&event))>
+                    <option value="" selected=true>-</option>
+                    {
+                        feat_options.into_iter().map(|name| view!{
+                            <option value=name.clone()>{name.clone()}</option>
+                        }).collect::<Vec<_>>()
+                    }
+                </select>
+                <button on:click=add_selected_feat>Add</button>
+            </div>
             <For
                 each={move || get_feat_list()}
                 key={move |feat| feat.name.clone()}
                 children=move |feat| {
                     let collapse = create_rw_signal(false);
+                    let confirm_remove = create_rw_signal(false);
+                    // Removing a feat undoes its proficiencies, rules and conditions, so it has to be confirmed
+                    let remove_view = {
+                        let change_feats = change_feats.clone();
+                        let feat_name = feat.name.clone();
+                        move || if confirm_remove.get() {
+                            let change_feats = change_feats.clone();
+                            let feat_name = feat_name.clone();
+                            view! {
+                                <div>{format!("Remove {feat_name} and undo what it changed?")}</div>
+                                <button on:click=move |_| change_feats(feat_name.clone(), false)>Yes</button>
+                                <button on:click=move |_| confirm_remove.set(false)>No</button>
+                            }.into_view()
+                        }
+                        else {
+                            view! {<button on:click=move |_| confirm_remove.set(true)>Remove</button>}.into_view()
+                        }
+                    };
                     view!{
                         <div class="flex-col bright-bg" on:click=move |_| collapse.update(|c| *c = !*c)>
                             <div class="flex-row feat-title-row ">
@@ -417,6 +612,7 @@ pub fn FeatView() -> impl IntoView {
                                 <TraitView trait_names=feat.traits.clone()/>
                                 <hr/>
                                 <p class="tiny-text" inner_html={let desc = feat.description.clone(); move || desc.clone()}></p>
+                                <div class="flex-row align-center" on:click=|event| event.stop_propagation()>{remove_view.clone()}</div>
                             </Show>
                         </div>
                     }
@@ -426,6 +622,81 @@ pub fn FeatView() -> impl IntoView {
     }
 }
 
+/// Focus spells granted by feats and other rules with the focus pool to cast them
+#[component]
+pub fn FocusView() -> impl IntoView {
+    let spells = use_context::<Vec<Spell>>().expect("FocusView: Expected spell list to be set");
+    let (read_character, write_character) = get_base_context("FocusView");
+    let sheet_error = get_sheet_error_context("FocusView");
+    let focus_spells = {
+        let spells = spells.clone();
+        move || read_character.with(|c| c.get_focus_spells(&spells))
+    };
+    let pool_text = {
+        let spells = spells.clone();
+        move || read_character.with(|c| format!("Focus [{0} / {1}]", c.get_focus_points(&spells), c.get_max_focus_points(&spells)))
+    };
+    let refocus = {
+        let spells = spells.clone();
+        move |_| write_character.update(|c| c.refocus(&spells))
+    };
+    view!{
+        <Show when={let focus_spells = focus_spells.clone(); move || !focus_spells().is_empty()}>
+            <div class="flex-col">
+                <div class="flex-row align-center">
+                    <h4>{pool_text.clone()}</h4>
+                    <button on:click=refocus.clone()>Refocus</button>
+                </div>
+                <For
+                    each=focus_spells.clone()
+                    key=|spell| spell.name.clone()
+                    children={
+                        let spells = spells.clone();
+                        move |spell| {
+                            let collapse = create_rw_signal(false);
+                            let cast_spell = {
+                                let spells = spells.clone();
+                                let spell_name = spell.name.clone();
+                                move |_| {
+                                    let mut result = Ok(());
+                                    write_character.update(|c| result = c.cast_focus_spell(&spell_name, &spells));
+                                    match result {
+                                        Ok(_) => sheet_error.set(SheetError::new("")),
+                                        Err(err) => {log!("FocusView: {err}"); sheet_error.set(SheetError::new(&err))},
+                                    }
+                                }
+                            };
+                            let heightened_text = {
+                                let spell = spell.clone();
+                                move || read_character.with(|c| spell.get_heightened_text(c.get_cantrip_rank()))
+                            };
+                            view!{
+                                <div class="flex-col bright-bg" on:click=move |_| collapse.update(|c| *c = !*c) on:contextmenu=cast_spell>
+                                    <div class="flex-row feat-title-row ">
+                                        <h4>{spell.name.clone()}</h4>
+                                        <Show when=move || spell.actions != 0>
+                                            <ActionView number=spell.actions/>
+                                        </Show>
+                                    </div>
+                                    <Show when=move || collapse.get()>
+                                        <TraitView trait_names=spell.traits.clone()/>
+                                        <hr/>
+                                        <p class="tiny-text" inner_html={let desc = spell.description.clone(); move || desc.clone()}></p>
+                                        {
+                                            let heightened_text = heightened_text.clone();
+                                            move || heightened_text().into_iter().map(|line| view!{<p class="tiny-text">{line}</p>}).collect::<Vec<_>>()
+                                        }
+                                    </Show>
+                                </div>
+                            }
+                        }
+                    }
+                />
+            </div>
+        </Show>
+    }
+}
+
 #[component]
 pub fn TraitView(
     trait_names: Vec<String>
@@ -438,17 +709,11 @@ pub fn TraitView(
                     log!("An empty trait was set somewhere"); String::from("No tooltip") 
                 }
                 else {
-                    let found_val = traitMap.get(&t).clone();
+                    //parameterized traits like "Deadly d8" share the description of their name
+                    let found_val = traitMap.get(&t).or_else(|| traitMap.get(&Trait::parse(&t).name));
                     match found_val {
                         Some(description) => String::from(description),
-                        None => {
-                            //some traits vary after the first word, so we reattempt after a splice
-                            let first_word = traitMap.get(t.split_whitespace().next().expect("TraitView: there should be at least one word in this trait by now"));
-                            match first_word {
-                                Some(description) => String::from(description),
-                                None => {log!("No tooltip was set for {0}", t); String::from("No tooltip") }
-                            }
-                        },
+                        None => {log!("No tooltip was set for {0}", t); String::from("No tooltip") }
                     }
                 };
                 
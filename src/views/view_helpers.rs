@@ -15,14 +15,14 @@ pub fn get_sheet_error_context(view_name: &str) -> RwSignal<SheetError> {
     use_context::<RwSignal<SheetError>>().expect(&format!("{name}: Expect error rw to be set"))
 }
 
+/// Writes a choice into a list slot, growing the list if needed
+pub fn set_slot(slots: &mut Vec<String>, indx: usize, value: String) {
+    if slots.len() <= indx {
+        slots.resize(indx + 1, String::from(""));
+    }
+    slots[indx] = value;
+}
+
 pub fn get_prefix(val:i32) -> String {
     if val > 0 {"+ "} else {""}.to_string()
 }
-
-pub fn check_character_flag(character: &Character, flag: &str) -> bool{
-    match character.flags.get(flag) {
-        Some(flag_v) => *flag_v,
-        None => false,
-    }
-    
-}
\ No newline at end of file